@@ -0,0 +1,18 @@
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=USAGE_TRACKER_DEFAULT_DATA");
+    println!("cargo:rustc-check-cfg=cfg(embedded_default_data)");
+
+    // builds with all features, e.g. in CI, fall back to empty data instead of failing
+    if env::var_os("CARGO_FEATURE_EMBEDDED_DEFAULT").is_some() {
+        match env::var_os("USAGE_TRACKER_DEFAULT_DATA") {
+            Some(_) => println!("cargo:rustc-cfg=embedded_default_data"),
+            None => println!(
+                "cargo:warning=`embedded-default` is enabled, but `USAGE_TRACKER_DEFAULT_DATA` \
+                 isn't set, so no default data is embedded"
+            ),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use std::{fmt, sync::Arc};
+
+/// A source for the current point in time.
+///
+/// By default, the system clock is used. Other implementations can be used to make calculations
+/// reproducible, e.g. within tests.
+pub trait Clock {
+    /// Provides the current point in time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A clock that provides the current time of the system.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that always provides the same point in time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Keeps the clock used by a `UsageInformation`.
+///
+/// The clock isn't part of the data, so it is shared between clones and ignored in comparisons.
+#[derive(Clone)]
+pub(crate) struct ClockHandle(Arc<dyn Clock + Send + Sync>);
+
+impl ClockHandle {
+    /// Creates a new handle for the provided clock.
+    pub(crate) fn new(clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self(clock)
+    }
+
+    /// Provides the current point in time according to the clock.
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        self.0.now()
+    }
+}
+
+impl Default for ClockHandle {
+    fn default() -> Self {
+        Self(Arc::new(SystemClock))
+    }
+}
+
+impl fmt::Debug for ClockHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // asking the clock for the time could have side effects, e.g. advancing a test clock
+        f.write_str("ClockHandle(..)")
+    }
+}
+
+impl PartialEq for ClockHandle {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ClockHandle {}
@@ -0,0 +1,135 @@
+use crate::UsageTrackerError;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A unit in which a duration can be specified.
+///
+/// Years are always treated as 365 days and months as 30 days.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DurationUnit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DurationUnit {
+    /// Creates a duration of `amount` times this unit.
+    ///
+    /// # Panics
+    /// Panics if the duration is out of the range `chrono::Duration` supports. Use
+    /// `checked_duration()` for amounts that aren't known to be small, e.g. user input.
+    pub fn to_duration(&self, amount: i64) -> Duration {
+        self.checked_duration(amount)
+            .expect("duration out of range")
+    }
+
+    /// Creates a duration of `amount` times this unit.
+    ///
+    /// Returns `None` if the duration is out of the range `chrono::Duration` supports.
+    pub fn checked_duration(&self, amount: i64) -> Option<Duration> {
+        let seconds = amount.checked_mul(self.seconds())?;
+        let max = Duration::max_value().num_seconds();
+        match (-max..=max).contains(&seconds) {
+            true => Some(Duration::seconds(seconds)),
+            false => None,
+        }
+    }
+
+    /// The number of seconds in this unit.
+    fn seconds(&self) -> i64 {
+        match self {
+            DurationUnit::Year => 365 * 24 * 60 * 60,
+            DurationUnit::Month => 30 * 24 * 60 * 60,
+            DurationUnit::Week => 7 * 24 * 60 * 60,
+            DurationUnit::Day => 24 * 60 * 60,
+            DurationUnit::Hour => 60 * 60,
+            DurationUnit::Minute => 60,
+            DurationUnit::Second => 1,
+        }
+    }
+}
+
+impl FromStr for DurationUnit {
+    type Err = UsageTrackerError;
+
+    /// Parses either the single letter abbreviation (`y`, `M`, `w`, `d`, `h`, `m`, `s`), a common
+    /// abbreviation (`yr`, `mo`, `wk`, `hr`, `min`, `sec`) or the full word (`year`, `month`, …) of
+    /// a unit.
+    ///
+    /// Single letters are case sensitive, so `M` is a month and `m` a minute. Abbreviations and
+    /// words are case insensitive and may be plural, e.g. `Days` or `hrs`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidDurationUnit`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "y" => return Ok(DurationUnit::Year),
+            "M" => return Ok(DurationUnit::Month),
+            "w" => return Ok(DurationUnit::Week),
+            "d" => return Ok(DurationUnit::Day),
+            "h" => return Ok(DurationUnit::Hour),
+            "m" => return Ok(DurationUnit::Minute),
+            "s" => return Ok(DurationUnit::Second),
+            _ => {}
+        }
+
+        let lower = s.trim().to_lowercase();
+        let singular = match lower.len() > 2 {
+            true => lower.strip_suffix('s').unwrap_or(&lower),
+            false => &lower,
+        };
+
+        for (unit, word, abbreviations) in UNITS {
+            if singular == *word || abbreviations.contains(&singular) {
+                return Ok(*unit);
+            }
+        }
+
+        // suggest all units starting with the same letter, or all units if none does
+        let mut suggestions: Vec<String> = UNITS
+            .iter()
+            .filter(|(_, word, _)| lower.chars().next() == word.chars().next())
+            .map(|(_, word, _)| word.to_string())
+            .collect();
+        if suggestions.is_empty() {
+            suggestions = UNITS.iter().map(|(_, word, _)| word.to_string()).collect();
+        }
+
+        Err(UsageTrackerError::InvalidDurationUnit {
+            unit: s.to_owned(),
+            suggestions,
+        })
+    }
+}
+
+/// All units with their full word and their abbreviations, in singular.
+const UNITS: &[(DurationUnit, &str, &[&str])] = &[
+    (DurationUnit::Year, "year", &["yr"]),
+    (DurationUnit::Month, "month", &["mo", "mon"]),
+    (DurationUnit::Week, "week", &["wk"]),
+    (DurationUnit::Day, "day", &[]),
+    (DurationUnit::Hour, "hour", &["hr"]),
+    (DurationUnit::Minute, "minute", &["min"]),
+    (DurationUnit::Second, "second", &["sec"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_duration_rejects_overflow() {
+        assert_eq!(
+            DurationUnit::Week.checked_duration(2),
+            Some(Duration::days(14))
+        );
+        assert_eq!(DurationUnit::Year.checked_duration(i64::MAX / 365), None);
+        assert_eq!(DurationUnit::Second.checked_duration(i64::MAX), None);
+        assert_eq!(DurationUnit::Day.checked_duration(99_999_999_999_999), None);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{JsonLinesEntry, LogEntry};
+use anyhow::Result;
+use chrono::Local;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+};
+use usage_tracker::UsageInformation;
+
+/// A file format data files can be stored in.
+pub trait DataFormat: Sync {
+    /// The name of the format, as shown to users.
+    fn name(&self) -> &'static str;
+
+    /// The file name extensions of the format. The first one is used when creating files.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Reads usage information from `file`.
+    fn load(&self, file: File) -> Result<UsageInformation>;
+
+    /// Writes `ui` to `file`, which is empty.
+    ///
+    /// If `epoch_seconds` is set, usages are written as seconds since the Unix epoch, see
+    /// `UsageInformation::epoch_seconds()`.
+    fn save(&self, file: File, ui: &UsageInformation, epoch_seconds: bool) -> Result<()>;
+
+    /// Specifies whether single usages can be appended to a file in this format, instead of
+    /// rewriting it. See `JsonLinesEntry`.
+    fn appendable(&self) -> bool {
+        false
+    }
+}
+
+/// All supported data file formats, in order of priority.
+pub const FORMATS: &[&dyn DataFormat] = &[
+    &Json,
+    &JsonLines,
+    #[cfg(feature = "cbor")]
+    &Cbor,
+];
+
+/// Finds the format `extension` belongs to.
+pub fn by_extension(extension: &str) -> Option<&'static dyn DataFormat> {
+    FORMATS
+        .iter()
+        .copied()
+        .find(|f| f.extensions().contains(&extension))
+}
+
+/// Provides the file name extensions of all supported formats, in order of priority.
+pub fn supported_extensions() -> Vec<&'static str> {
+    FORMATS
+        .iter()
+        .flat_map(|f| f.extensions().iter().copied())
+        .collect()
+}
+
+/// Pretty printed JSON.
+pub struct Json;
+
+impl DataFormat for Json {
+    fn name(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn load(&self, file: File) -> Result<UsageInformation> {
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    fn save(&self, file: File, ui: &UsageInformation, epoch_seconds: bool) -> Result<()> {
+        match epoch_seconds {
+            true => Ok(serde_json::to_writer_pretty(file, &ui.epoch_seconds())?),
+            false => Ok(serde_json::to_writer_pretty(file, ui)?),
+        }
+    }
+}
+
+/// JSON Lines, with an optional snapshot of all data followed by appended usages.
+pub struct JsonLines;
+
+impl DataFormat for JsonLines {
+    fn name(&self) -> &'static str {
+        "JSONL"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["jsonl"]
+    }
+
+    /// Folds the lines of the file into usage information.
+    ///
+    /// A snapshot replaces everything read before it, while usages are recorded on top of the
+    /// current data. Usages that can't be recorded, e.g. because their object was archived after
+    /// they were appended, are skipped with a warning, so they can't make the file unreadable.
+    fn load(&self, file: File) -> Result<UsageInformation> {
+        let mut info = UsageInformation::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(&line)? {
+                JsonLinesEntry::Snapshot(snapshot) => info = snapshot,
+                JsonLinesEntry::Use(entry) => {
+                    let LogEntry {
+                        name,
+                        timestamp,
+                        source,
+                        add_if_new,
+                    } = entry;
+                    if let Err(e) = info.record_use_at(&name, timestamp, source, add_if_new) {
+                        crate::warn(&format!(
+                            "skipping usage of \"{}\" at {}: {}",
+                            name,
+                            timestamp.with_timezone(&Local),
+                            e
+                        ));
+                    }
+                }
+            }
+        }
+
+        // replaying the appended usages doesn't change the file
+        info.mark_clean();
+        Ok(info)
+    }
+
+    /// Writes all data as a single snapshot line.
+    fn save(&self, mut file: File, ui: &UsageInformation, epoch_seconds: bool) -> Result<()> {
+        match epoch_seconds {
+            true => serde_json::to_writer(&file, &ui.epoch_seconds())?,
+            false => serde_json::to_writer(&file, ui)?,
+        }
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn appendable(&self) -> bool {
+        true
+    }
+}
+
+/// CBOR, only available if built with the `cbor` feature.
+#[cfg(feature = "cbor")]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl DataFormat for Cbor {
+    fn name(&self) -> &'static str {
+        "CBOR"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["cbor"]
+    }
+
+    fn load(&self, file: File) -> Result<UsageInformation> {
+        Ok(ciborium::de::from_reader(file)?)
+    }
+
+    fn save(&self, file: File, ui: &UsageInformation, epoch_seconds: bool) -> Result<()> {
+        match epoch_seconds {
+            true => Ok(ciborium::ser::into_writer(&ui.epoch_seconds(), file)?),
+            false => Ok(ciborium::ser::into_writer(ui, file)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use std::{fs, path::PathBuf, sync::Arc};
+    use usage_tracker::{DurationUnit, FixedClock};
+
+    /// Provides a path for a temporary file that is unique to the test `name`.
+    fn temp_path(name: &str, format: &dyn DataFormat) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "usage-tracker-{}-{}.{}",
+            std::process::id(),
+            name,
+            format.extensions()[0]
+        ))
+    }
+
+    /// Saves `info` in `format` and loads it again.
+    fn round_trip(
+        name: &str,
+        format: &dyn DataFormat,
+        info: &UsageInformation,
+        epoch_seconds: bool,
+    ) -> UsageInformation {
+        let path = temp_path(name, format);
+        format
+            .save(File::create(&path).unwrap(), info, epoch_seconds)
+            .unwrap();
+        let loaded = format.load(File::open(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+        loaded.unwrap()
+    }
+
+    /// Provides usage information that makes use of everything that is stored.
+    fn populated() -> UsageInformation {
+        let created = Utc.ymd(2021, 1, 1).and_hms(8, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(created)));
+
+        info.add(&"cheese".to_owned()).unwrap();
+        let milk = created + Duration::hours(1);
+        info.record_use_at("milk", milk, Some("laptop".to_owned()), true)
+            .unwrap();
+        info.record_use_at("bread", created + Duration::days(1), None, true)
+            .unwrap();
+        info.set_target(&"milk".to_owned(), Some((5, DurationUnit::Week)))
+            .unwrap();
+
+        info.start_use("editor", true).unwrap();
+        info.set_clock(Arc::new(FixedClock(created + Duration::hours(2))));
+        info.stop_use("editor").unwrap();
+        info.set_clock(Arc::new(FixedClock(created + Duration::hours(3))));
+        info.start_use("editor", false).unwrap();
+
+        info.alias(&"moo juice".to_owned(), &"milk".to_owned())
+            .unwrap();
+        info.group(&"groceries".to_owned(), &"milk".to_owned())
+            .unwrap();
+        info.group(&"groceries".to_owned(), &"bread".to_owned())
+            .unwrap();
+        info.archive(&"cheese".to_owned()).unwrap();
+
+        info
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let info = populated();
+        assert_eq!(round_trip("json", &Json, &info, false), info);
+        assert_eq!(round_trip("json-epoch", &Json, &info, true), info);
+    }
+
+    #[test]
+    fn jsonl_round_trip() {
+        let info = populated();
+        assert_eq!(round_trip("jsonl", &JsonLines, &info, false), info);
+        assert_eq!(round_trip("jsonl-epoch", &JsonLines, &info, true), info);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn cbor_round_trip() {
+        let info = populated();
+        assert_eq!(round_trip("cbor", &Cbor, &info, false), info);
+        assert_eq!(round_trip("cbor-epoch", &Cbor, &info, true), info);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn cbor_loads_files_written_by_other_tools() {
+        // {"usage_information": {"milk": {"usages": ["2021-01-01T00:00:00Z", 1609545600],
+        // "created_at": "2021-01-01T00:00:00Z"}}}
+        let bytes = [
+            &b"\xa1\x71usage_information\xa1\x64milk\xa2"[..],
+            &b"\x66usages\x82\x742021-01-01T00:00:00Z\x1a\x5f\xef\xb7\x80"[..],
+            &b"\x6acreated_at\x742021-01-01T00:00:00Z"[..],
+        ]
+        .concat();
+        let path = temp_path("cbor-foreign", &Cbor);
+        fs::write(&path, bytes).unwrap();
+        let loaded = Cbor.load(File::open(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        let info = loaded.unwrap();
+        let first = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let usages = info.usages(&"milk".to_owned()).unwrap();
+        assert_eq!(
+            usages.list(),
+            vec![first, first + chrono::Duration::days(1)]
+        );
+        assert_eq!(usages.created_at(), &first);
+        assert_eq!(info.list(), vec!["milk"]);
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The size of the buckets usages are grouped into, e.g. to find the busiest period.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Granularity {
+    /// The hour of the day.
+    Hour,
+    /// The day of the week.
+    Weekday,
+    /// The month of the year.
+    Month,
+}
+
+impl Granularity {
+    /// Provides the bucket `timestamp` belongs to, in the timezone `tz`.
+    pub fn bucket_of<Tz: TimeZone>(&self, timestamp: &DateTime<Utc>, tz: &Tz) -> Bucket {
+        let local = timestamp.with_timezone(tz);
+        match self {
+            Granularity::Hour => Bucket::Hour(local.hour()),
+            Granularity::Weekday => Bucket::Weekday(local.weekday().num_days_from_monday()),
+            Granularity::Month => Bucket::Month(local.month()),
+        }
+    }
+}
+
+/// A recurring period of time usages can be grouped into.
+///
+/// Buckets of the same granularity are ordered chronologically, starting at midnight, Monday or
+/// January respectively.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Bucket {
+    /// The hour of the day, from 0 to 23.
+    Hour(u32),
+    /// The day of the week, from 0 (Monday) to 6 (Sunday).
+    Weekday(u32),
+    /// The month of the year, from 1 (January) to 12 (December).
+    Month(u32),
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const WEEKDAYS: [&str; 7] = [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ];
+        const MONTHS: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+
+        match self {
+            Bucket::Hour(h) => write!(f, "{:02}:00 - {:02}:00", h, (h + 1) % 24),
+            Bucket::Weekday(d) => f.write_str(WEEKDAYS[*d as usize % 7]),
+            Bucket::Month(m) => f.write_str(MONTHS[(*m as usize + 11) % 12]),
+        }
+    }
+}
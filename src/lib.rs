@@ -13,23 +13,104 @@
 //! `UsageTrackerError` as error type. The documentation of those methods lists all possible errors
 //! that can occur within that method.
 //!
+//! To make several changes that can be undone as a whole, wrap the data in a `Transaction`.
+//!
 //! As far as I can tell, the library should not panic no matter what input you provide.
 
+mod clock;
+mod duration_unit;
+mod granularity;
+mod list_filter;
+mod observers;
+mod precision;
+mod transaction;
 mod usages;
 
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use clock::ClockHandle;
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use duration_unit::DurationUnit;
+pub use granularity::{Bucket, Granularity};
+pub use list_filter::ListFilter;
+use observers::Observers;
+pub use observers::RecordCallback;
+pub use precision::Precision;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::{btree_map::Entry::Occupied, BTreeMap};
+use std::{
+    collections::{
+        btree_map::Entry::{Occupied, Vacant},
+        BTreeMap, BTreeSet,
+    },
+    sync::Arc,
+};
 use thiserror::Error;
-pub use usages::Usages;
+pub use transaction::Transaction;
+use usages::EpochSecondsUsages;
+pub use usages::{Usage, Usages};
+
+/// The version of the data layout `UsageInformation` is serialized in.
+///
+/// - `1`: the layout of v0.1, a map from object names to their usages.
+/// - `2`: the layout since v0.2, a `UsageInformation` struct.
+/// - `3`: usages with details, like their source, are stored as objects instead of timestamps.
+///
+/// The version only changes if data written in the new layout can't be read as the old layout
+/// anymore, or the other way around. Adding optional fields doesn't change it.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// The number of windows `UsageInformation::smoothed_rate()` averages over.
+pub const SMOOTHING_WINDOWS: i32 = 4;
+
+/// The minimum number of usages `UsageInformation::usage_trend()` fits a trend to.
+pub const MIN_TREND_USAGES: usize = 5;
 
 /// All errors the library's public interface can return.
 #[derive(Error, Debug)]
 pub enum UsageTrackerError {
+    /// Tried to remove an alias that doesn't exist.
+    #[error("alias \"{alias}\" doesn't exist")]
+    AliasNotDefined { alias: String },
+
+    /// Tried to use the same name for an alias and an object.
+    #[error("\"{name}\" is already used as an object or alias name")]
+    AliasShadowsObject { name: String },
+
     /// The loading (most likely parsing) of a RON file failed. Contains the root cause.
     #[error("RON file could not be loaded")]
     FileLoadErrorRon(#[source] ron::Error),
 
+    /// Tried to access a group that doesn't exist.
+    #[error("group \"{group}\" doesn't exist")]
+    GroupNotDefined { group: String },
+
+    /// Tried to predict the need of an object with too few usages to make a prediction.
+    #[error("object \"{name}\" has too few usages for a prediction")]
+    InsufficientData { name: String },
+
+    /// Tried to parse a duration unit that doesn't exist.
+    #[error("duration unit \"{unit}\" doesn't exist, did you mean {}?", .suggestions.join(" or "))]
+    InvalidDurationUnit {
+        unit: String,
+        suggestions: Vec<String>,
+    },
+
+    /// Tried to use a name that isn't allowed for objects.
+    #[error("object name \"{name}\" is invalid: {reason}")]
+    InvalidName { name: String, reason: String },
+
+    /// Tried to use a regular expression that can't be parsed.
+    #[error("pattern \"{pattern}\" is invalid: {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+
+    /// Tried to check the progress of an object without a target rate.
+    #[error("object \"{name}\" has no target rate")]
+    NoTarget { name: String },
+
+    /// Tried to record a usage of an archived object.
+    #[error("object \"{name}\" is archived")]
+    ObjectArchived { name: String },
+
     /// Tried to add a new object to keep track of, but object with same name is already tracked.
     #[error("object \"{name}\" is already tracked")]
     ObjectAlreadyTracked { name: String },
@@ -41,20 +122,70 @@ pub enum UsageTrackerError {
     /// Tried to access an object that is not kept track of.
     #[error("object \"{name}\" doesn't exist")]
     ObjectNotTracked { name: String },
+
+    /// Tried to start a session of an object while another one is still running.
+    #[error("object \"{name}\" already has a running session")]
+    SessionAlreadyRunning { name: String },
+
+    /// Tried to stop the session of an object that has no running session.
+    #[error("object \"{name}\" has no running session")]
+    SessionNotRunning { name: String },
 }
 
 /// A struct that keeps the records for all tracked objects.
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+///
+/// Two instances are equal if they contain the same data, regardless of whether they are dirty.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct UsageInformation {
     usage_information: BTreeMap<String, Usages>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    aliases: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    groups: BTreeMap<String, BTreeSet<String>>,
+    #[serde(skip)]
+    observers: Observers,
+    #[serde(skip)]
+    clock: ClockHandle,
+    /// Whether the data was changed since it was created, loaded or last marked as clean.
+    #[serde(skip)]
+    dirty: bool,
+    /// Whether usages in the future are taken into account for counts and predictions.
+    #[serde(skip)]
+    include_future: bool,
+}
+
+impl PartialEq for UsageInformation {
+    fn eq(&self, other: &Self) -> bool {
+        self.usage_information == other.usage_information
+            && self.aliases == other.aliases
+            && self.groups == other.groups
+    }
+}
+
+impl Eq for UsageInformation {}
+
+/// A view of `UsageInformation` that serializes the usages as seconds since the Unix epoch, see
+/// `UsageInformation::epoch_seconds()`.
+#[derive(Serialize)]
+pub struct EpochSeconds<'a> {
+    usage_information: BTreeMap<&'a String, EpochSecondsUsages<'a>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    aliases: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    groups: &'a BTreeMap<String, BTreeSet<String>>,
 }
 
 impl UsageInformation {
     /// Adds a new object to keep track of.
     ///
     /// # Possible errors
+    /// - `UsageTrackerError::AliasShadowsObject`
+    /// - `UsageTrackerError::InvalidName`
     /// - `UsageTrackerError::ObjectAlreadyTracked`
     pub fn add(&mut self, name: &String) -> Result<(), UsageTrackerError> {
+        validate_name(name)?;
+        self.check_not_alias(name)?;
+
         if self.usage_information.contains_key(name) {
             return Err(UsageTrackerError::ObjectAlreadyTracked {
                 name: name.to_owned(),
@@ -62,14 +193,366 @@ impl UsageInformation {
         }
 
         self.usage_information
-            .insert(name.to_owned(), Usages::new());
+            .insert(name.to_owned(), Usages::new_at(self.clock.now()));
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    /// Defines `alias` as an alternative name for the object `target`.
+    ///
+    /// Recording, showing and counting usages resolves aliases to their target. If `alias` already
+    /// exists, it is changed to point to `target`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::AliasShadowsObject`
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn alias(&mut self, alias: &String, target: &String) -> Result<(), UsageTrackerError> {
+        validate_name(alias)?;
+        self.tracked(target)?;
+
+        if self.usage_information.contains_key(alias) {
+            return Err(UsageTrackerError::AliasShadowsObject {
+                name: alias.to_owned(),
+            });
+        }
+
+        if self.aliases.insert(alias.to_owned(), target.to_owned()) != Some(target.to_owned()) {
+            self.dirty = true;
+        }
 
         Ok(())
     }
 
+    /// Provides read access to all aliases and their targets.
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    /// Provides a copy of the data with all names replaced by stable pseudonyms, e.g. to share
+    /// usage patterns without revealing which objects are tracked.
+    ///
+    /// Names of objects, aliases and groups are replaced, and so are the sources of usages, as they
+    /// might reveal names as well. All timestamps are kept. The same name is always replaced by the
+    /// same pseudonym. The second value maps the original names and sources to their pseudonyms.
+    pub fn anonymized(&self) -> (Self, BTreeMap<String, String>) {
+        let mut mapping = BTreeMap::new();
+        let mut anonymize = |name: &String| {
+            mapping
+                .entry(name.to_owned())
+                .or_insert_with(|| anonymize_name(name))
+                .to_owned()
+        };
+
+        let mut anonymized = Self::new();
+        for (name, usages) in &self.usage_information {
+            let mut usages = usages.clone();
+            usages.map_sources(&mut anonymize);
+            anonymized.usage_information.insert(anonymize(name), usages);
+        }
+        for (alias, target) in &self.aliases {
+            anonymized
+                .aliases
+                .insert(anonymize(alias), anonymize(target));
+        }
+        for (group, members) in &self.groups {
+            let members = members.iter().map(&mut anonymize).collect();
+            anonymized.groups.insert(anonymize(group), members);
+        }
+
+        (anonymized, mapping)
+    }
+
+    /// Archives an object.
+    ///
+    /// Archived objects keep their usages, but no new usages can be recorded.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn archive(&mut self, name: &String) -> Result<(), UsageTrackerError> {
+        self.set_archived(name, true)
+    }
+
+    /// Finds the bucket of the specified `granularity` in which an object was used most often.
+    ///
+    /// Buckets are determined in the timezone `tz`. If multiple buckets have the same number of
+    /// usages, the earliest one is returned. Returns the bucket and its number of usages.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNeverUsed`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn busiest<Tz: TimeZone>(
+        &self,
+        name: &String,
+        granularity: Granularity,
+        tz: &Tz,
+    ) -> Result<(Bucket, usize), UsageTrackerError> {
+        let mut counts: BTreeMap<Bucket, usize> = BTreeMap::new();
+        for u in self.usages(name)?.entries() {
+            *counts.entry(granularity.bucket_of(u.at(), tz)).or_default() += 1;
+        }
+
+        // the buckets are iterated in order, so only later buckets with more usages replace one
+        counts
+            .into_iter()
+            .fold(
+                None,
+                |max: Option<(Bucket, usize)>, (bucket, count)| match max {
+                    Some((_, max_count)) if max_count >= count => max,
+                    _ => Some((bucket, count)),
+                },
+            )
+            .ok_or_else(|| UsageTrackerError::ObjectNeverUsed {
+                name: name.to_owned(),
+            })
+    }
+
     /// Removes **all** objects permanently.
     pub fn clear(&mut self) {
+        if !self.usage_information.is_empty() || !self.aliases.is_empty() || !self.groups.is_empty()
+        {
+            self.dirty = true;
+        }
+
         self.usage_information.clear();
+        self.aliases.clear();
+        self.groups.clear();
+    }
+
+    /// Counts how many usages of `a` have at least one usage of `b` within `window` before or after
+    /// them.
+    ///
+    /// If the objects were never used close to each other, the count is zero.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn cooccurrence(
+        &self,
+        a: &String,
+        b: &String,
+        window: Duration,
+    ) -> Result<usize, UsageTrackerError> {
+        let a = self.usages(a)?.list();
+        let b = self.usages(b)?.list();
+
+        // both vectors are sorted, so the first candidate in b only ever moves forward
+        let mut j = 0;
+        let mut count = 0;
+        for ta in &a {
+            while j < b.len() && b[j] < *ta - window {
+                j += 1;
+            }
+            if j < b.len() && b[j] <= *ta + window {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Copies the usages of an object to a new object.
+    ///
+    /// Afterwards both objects are independent of each other.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::AliasShadowsObject`
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectAlreadyTracked`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn copy(&mut self, from: &String, to: &String) -> Result<(), UsageTrackerError> {
+        validate_name(to)?;
+        self.check_not_alias(to)?;
+
+        let usages = self.usages(from)?.clone();
+
+        if self.usage_information.contains_key(to) {
+            return Err(UsageTrackerError::ObjectAlreadyTracked {
+                name: to.to_owned(),
+            });
+        }
+
+        self.usage_information.insert(to.to_owned(), usages);
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    /// Provides the number of usages of an object.
+    ///
+    /// Usages in the future are only counted if `set_include_future()` enabled that.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn count(&self, name: &String) -> Result<usize, UsageTrackerError> {
+        Ok(self.counted(self.usages(name)?).len())
+    }
+
+    /// Provides the number of objects whose names match the regular expression `pattern`.
+    ///
+    /// Matching works like in `search()`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidPattern`
+    pub fn count_matching(&self, pattern: &str) -> Result<usize, UsageTrackerError> {
+        let regex = Regex::new(pattern).map_err(|e| UsageTrackerError::InvalidPattern {
+            pattern: pattern.to_owned(),
+            reason: e.to_string(),
+        })?;
+
+        Ok(self.search(&regex).len())
+    }
+
+    /// Provides the number of usages of an object within segments of the day, see
+    /// `Usages::count_per_segment()`.
+    ///
+    /// Usages in the future are only counted if `set_include_future()` enabled that.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn count_per_segment<Tz: TimeZone>(
+        &self,
+        name: &String,
+        starts: &[u32],
+        tz: &Tz,
+    ) -> Result<Vec<usize>, UsageTrackerError> {
+        Ok(usages::count_per_segment(
+            self.counted(self.usages(name)?),
+            starts,
+            tz,
+        ))
+    }
+
+    /// Provides the number of usages of an object at or after `since`.
+    ///
+    /// Usages in the future are only counted if `set_include_future()` enabled that.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn count_since(
+        &self,
+        name: &String,
+        since: &DateTime<Utc>,
+    ) -> Result<usize, UsageTrackerError> {
+        Ok(count_since(self.counted(self.usages(name)?), since))
+    }
+
+    /// Provides the usages of an object that are taken into account for counts and predictions, in
+    /// chronological order.
+    ///
+    /// Usages in the future are only included if `set_include_future()` enabled that.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn counted_usages(&self, name: &String) -> Result<&[Usage], UsageTrackerError> {
+        Ok(self.counted(self.usages(name)?))
+    }
+
+    /// Finds groups of objects whose names are equal after applying `normalize`.
+    ///
+    /// Only groups with at least two objects are returned.
+    pub fn duplicates<F>(&self, normalize: F) -> Vec<Vec<&String>>
+    where
+        F: Fn(&str) -> String,
+    {
+        let mut groups: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+        for k in self.usage_information.keys() {
+            groups.entry(normalize(k)).or_default().push(k);
+        }
+
+        groups.into_values().filter(|g| g.len() > 1).collect()
+    }
+
+    /// Provides a view of the data that serializes the usages as seconds since the Unix epoch.
+    ///
+    /// This roughly halves the size of large JSON files, but sub-second precision is lost. Loading
+    /// accepts both this and the default representation.
+    pub fn epoch_seconds(&self) -> EpochSeconds<'_> {
+        EpochSeconds {
+            usage_information: self
+                .usage_information
+                .iter()
+                .map(|(k, v)| (k, EpochSecondsUsages::from(v)))
+                .collect(),
+            aliases: &self.aliases,
+            groups: &self.groups,
+        }
+    }
+
+    /// Provides all usages of all objects as `(name, timestamp)` events.
+    ///
+    /// The objects are visited in alphabetical order. The usages of a single object are yielded in
+    /// chronological order, but usages of different objects are not interleaved. Use
+    /// `events_sorted()` to get all events in chronological order.
+    pub fn events(&self) -> impl Iterator<Item = (&String, &DateTime<Utc>)> {
+        self.usage_information
+            .iter()
+            .flat_map(|(name, usages)| usages.entries().iter().map(move |u| (name, u.at())))
+    }
+
+    /// Provides all usages of all objects as `(name, timestamp)` events, in chronological order.
+    ///
+    /// Events with the same timestamp are ordered alphabetically by the name of their object.
+    pub fn events_sorted(&self) -> Vec<(&String, &DateTime<Utc>)> {
+        let mut events: Vec<_> = self.events().collect();
+
+        // the usages of every object are already sorted, so the stable sort merges those runs
+        events.sort_by_key(|(_, timestamp)| *timestamp);
+        events
+    }
+
+    /// Adds the object `member` to the group `group`, creating the group if necessary.
+    ///
+    /// Groups combine several objects, e.g. for the `stats` of all editors.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn group(&mut self, group: &String, member: &String) -> Result<(), UsageTrackerError> {
+        validate_name(group)?;
+        self.tracked(member)?;
+
+        if self
+            .groups
+            .entry(group.to_owned())
+            .or_default()
+            .insert(member.to_owned())
+        {
+            self.dirty = true;
+        }
+
+        Ok(())
+    }
+
+    /// Provides read access to all groups and their members.
+    ///
+    /// Groups follow their members: removed objects leave all groups, renamed and merged objects
+    /// are replaced by their new name, and groups without members are removed.
+    pub fn groups(&self) -> &BTreeMap<String, BTreeSet<String>> {
+        &self.groups
+    }
+
+    /// Converts already parsed data in the layout of v0.1 into a UsageInformation object.
+    ///
+    /// # Explanation
+    /// With v0.2, the data layout was changed. In v0.1, the data was a map from object names to
+    /// their usages, without any surrounding structure. This function converts such a map into the
+    /// data structure of v0.2. `load_usage_information_from_ron_file()` uses it for files.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::FileLoadErrorRon`
+    pub fn from_v01(value: ron::Value) -> Result<Self, UsageTrackerError> {
+        Ok(Self {
+            usage_information: value
+                .into_rust()
+                .map_err(UsageTrackerError::FileLoadErrorRon)?,
+            aliases: BTreeMap::new(),
+            groups: BTreeMap::new(),
+            observers: Observers::default(),
+            clock: ClockHandle::default(),
+            dirty: false,
+            include_future: false,
+        })
     }
 
     /// Provides a vector with all existing keys.
@@ -77,6 +560,17 @@ impl UsageInformation {
         self.usage_information.keys().collect()
     }
 
+    /// Provides a vector with the names of all objects that fulfill the conditions of `filter`.
+    pub fn list_filtered(&self, filter: &ListFilter) -> Vec<&String> {
+        let now = self.clock.now();
+
+        self.usage_information
+            .iter()
+            .filter(|(_, v)| filter.matches(v, now))
+            .map(|(k, _)| k)
+            .collect()
+    }
+
     /// Provides read access to all stored data.
     pub fn list_verbose(&self) -> &BTreeMap<String, Usages> {
         &self.usage_information
@@ -102,114 +596,1627 @@ impl UsageInformation {
     where
         R: std::io::Read,
     {
-        Ok(Self {
-            usage_information: ron::de::from_reader(rdr)
-                .or_else(|e| return Err(UsageTrackerError::FileLoadErrorRon(e)))?,
-        })
+        let value = ron::de::from_reader(rdr)
+            .or_else(|e| return Err(UsageTrackerError::FileLoadErrorRon(e)))?;
+
+        Self::from_v01(value)
+    }
+
+    /// Merges the usages of an object into another object and removes the former object.
+    ///
+    /// The merged object is built completely before the map is changed, so a failure during the
+    /// merge leaves both objects untouched.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn merge(&mut self, from: &String, into: &String) -> Result<(), UsageTrackerError> {
+        self.tracked(from)?;
+        self.tracked(into)?;
+
+        if from != into {
+            let mut merged = self.usage_information[into].clone();
+            merged.merge(self.usage_information[from].clone());
+
+            self.usage_information.insert(into.to_owned(), merged);
+            self.usage_information.remove(from);
+            self.retarget_aliases(from, Some(into));
+            self.dirty = true;
+        }
+
+        Ok(())
+    }
+
+    /// Adds all objects, usages, aliases and groups of `other` to this data.
+    ///
+    /// Objects tracked in both are merged like with `Usages::merge`. Objects of `other` whose name
+    /// is already used by an alias are skipped, their names are returned. Aliases of `other` are
+    /// only added if their name isn't already used by an object or alias. Groups existing in both
+    /// get the members of both.
+    pub fn merge_from(&mut self, other: UsageInformation) -> Vec<String> {
+        let mut skipped = Vec::new();
+        for (name, usages) in other.usage_information {
+            if self.aliases.contains_key(&name) {
+                skipped.push(name);
+                continue;
+            }
+
+            match self.usage_information.get_mut(&name) {
+                Some(existing) => existing.merge(usages),
+                None => {
+                    self.usage_information.insert(name, usages);
+                }
+            }
+            self.dirty = true;
+        }
+
+        for (alias, target) in other.aliases {
+            if !self.usage_information.contains_key(&alias) && !self.aliases.contains_key(&alias) {
+                self.aliases.insert(alias, target);
+                self.dirty = true;
+            }
+        }
+
+        for (group, members) in other.groups {
+            let existing = self.groups.entry(group).or_default();
+            for member in members {
+                if existing.insert(member) {
+                    self.dirty = true;
+                }
+            }
+        }
+
+        skipped
     }
 
     /// Creates a new, empty UsageInformation object.
     pub fn new() -> Self {
         Self {
             usage_information: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+            groups: BTreeMap::new(),
+            observers: Observers::default(),
+            clock: ClockHandle::default(),
+            dirty: false,
+            include_future: false,
         }
     }
 
-    /// Removes usages from an object.
+    /// Specifies whether the data was changed since it was created, loaded or last marked as
+    /// clean.
     ///
-    /// If `before` is `None`, all usages are removed. Otherwise, only usages before `before` are
-    /// removed.
-    ///
-    /// # Possible errors:
-    /// - `UsageTrackerError::ObjectNotTracked`
-    pub fn prune(
-        &mut self,
-        name: &String,
-        before: &Option<chrono::DateTime<chrono::Utc>>,
-    ) -> Result<(), UsageTrackerError> {
-        if let Occupied(mut e) = self.usage_information.entry(name.to_owned()) {
-            let usages = e.get_mut();
+    /// Only actual changes make the data dirty. For example, archiving an object that already is
+    /// archived doesn't.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
 
-            if before.is_some() {
-                usages.prune(before.unwrap());
-            } else {
-                usages.clear();
-            }
+    /// Marks the data as clean, e.g. after it has been saved.
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
 
-            return Ok(());
-        } else {
-            return Err(UsageTrackerError::ObjectNotTracked {
-                name: name.to_owned(),
-            });
+    /// Brings the stored data into its canonical form, by sorting the usages of every object
+    /// chronologically. Usages at the same point in time are all kept.
+    ///
+    /// Returns how many objects had to be changed.
+    pub fn rebuild(&mut self) -> usize {
+        let modified = self
+            .usage_information
+            .values_mut()
+            .map(Usages::rebuild)
+            .filter(|&changed| changed)
+            .count();
+        if modified > 0 {
+            self.dirty = true;
         }
+        modified
     }
 
-    /// Records a new usage of an object.
+    /// Provides the `n`th most recent usage of an object, where `0` is the most recent one.
+    ///
+    /// If the object was used `n` times or fewer, `None` is returned.
     ///
     /// # Possible errors
     /// - `UsageTrackerError::ObjectNotTracked`
-    pub fn record_use(&mut self, name: &String, add_if_new: bool) -> Result<(), UsageTrackerError> {
-        if !add_if_new && !self.usage_information.contains_key(name) {
-            return Err(UsageTrackerError::ObjectNotTracked {
-                name: name.to_owned(),
-            });
-        }
+    pub fn nth_recent(&self, name: &String, n: usize) -> Result<Option<&Usage>, UsageTrackerError> {
+        Ok(self.usages(name)?.nth_recent(n))
+    }
 
+    /// Provides a vector with the names of all objects that were used at least once on `date`.
+    ///
+    /// The day is determined in the timezone `tz`, i.e. it starts and ends at midnight in `tz`.
+    pub fn objects_used_on<Tz: TimeZone>(&self, date: NaiveDate, tz: &Tz) -> Vec<&String> {
         self.usage_information
-            .entry(name.to_owned())
-            .or_insert(Usages::new())
-            .record_usage();
-        Ok(())
+            .iter()
+            .filter(|(_, v)| {
+                v.entries()
+                    .iter()
+                    .any(|u| u.at().with_timezone(tz).naive_local().date() == date)
+            })
+            .map(|(k, _)| k)
+            .collect()
     }
 
-    /// Removes a currently tracked object permanently.
-    pub fn remove(&mut self, name: &String) {
-        if self.usage_information.contains_key(name) {
-            self.usage_information.remove(name);
-        }
+    /// Registers a callback that is invoked whenever a usage is recorded.
+    ///
+    /// The callback receives the name of the object and the timestamp of the new usage. It fires
+    /// after the usage has been stored. Callbacks are not carried over to clones.
+    pub fn on_record(&mut self, cb: RecordCallback) {
+        self.observers.add_on_record(cb);
     }
 
-    /// Calculates the number of usages of the specified object within the specified amount of time.
+    /// Predicts when an object will be used next, e.g. to remind the user of it.
     ///
-    /// This works by calculating how much the specified time frame is in comparison to the time
-    /// since the oldest recorded usage. This relationship is the multiplied by the number of total
-    /// uses, to calculate a specific number.
+    /// This is a naive estimate: the mean gap between the usages is added to the last of them. It
+    /// works well for roughly periodic usage, but doesn't take trends or weekly patterns into
+    /// account. The predicted point in time can lie before `now` if the object is overdue. Returns
+    /// `None` if the object was used fewer than two times.
+    ///
+    /// Only usages up to `now` are taken into account, unless `set_include_future()` enabled
+    /// taking usages in the future into account as well.
     ///
     /// # Possible errors
-    /// - `UsageTrackerError::ObjectNeverUsed`
     /// - `UsageTrackerError::ObjectNotTracked`
-    pub fn usage(&self, name: &String, time_frame: &Duration) -> Result<f64, UsageTrackerError> {
-        if !self.usage_information.contains_key(name) {
-            return Err(UsageTrackerError::ObjectNotTracked {
-                name: name.to_owned(),
-            });
+    pub fn predict_next_use(
+        &self,
+        name: &String,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, UsageTrackerError> {
+        let mut counted = self.counted(self.usages(name)?);
+        if !self.include_future {
+            counted = &counted[..counted.partition_point(|u| *u.at() <= now)];
         }
 
-        let ui = &self.usage_information[name].list();
-        if ui.is_empty() {
-            return Err(UsageTrackerError::ObjectNeverUsed {
-                name: name.to_owned(),
-            });
+        match (counted.first(), counted.last()) {
+            (Some(first), Some(last)) if counted.len() >= 2 => {
+                let mean_gap = (*last.at() - *first.at()) / (counted.len() as i32 - 1);
+                Ok(Some(*last.at() + mean_gap))
+            }
+            _ => Ok(None),
         }
-
-        let time_since_first_use = Utc::now() - ui[0];
-        let percentage_of_time_since_first_use =
-            time_frame.num_seconds() as f64 / time_since_first_use.num_seconds() as f64;
-
-        Ok(percentage_of_time_since_first_use * ui.len() as f64)
     }
 
-    /// Provides the usages for a specific object.
+    /// Compares how often an object was used within the current period to its target rate.
+    ///
+    /// The current period has the length of the target rates unit and ends now. For a target of 5
+    /// times per week, the usages within the last 7 days are counted. Returns the actual number of
+    /// usages and the targeted number of usages.
     ///
     /// # Possible errors
+    /// - `UsageTrackerError::NoTarget`
     /// - `UsageTrackerError::ObjectNotTracked`
-    pub fn usages(&self, name: &String) -> Result<&Usages, UsageTrackerError> {
-        if !self.usage_information.contains_key(name) {
-            return Err(UsageTrackerError::ObjectNotTracked {
+    pub fn progress(&self, name: &String) -> Result<(usize, u32), UsageTrackerError> {
+        let usages = self.usages(name)?;
+        let (target, unit) = usages
+            .target_rate()
+            .ok_or_else(|| UsageTrackerError::NoTarget {
                 name: name.to_owned(),
-            });
-        }
+            })?;
+
+        let actual = count_since(
+            self.counted(usages),
+            &(self.clock.now() - unit.to_duration(1)),
+        );
+        Ok((actual, target))
+    }
+
+    /// Removes usages from an object.
+    ///
+    /// If `before` is `None`, all usages are removed. Otherwise, only usages before `before` are
+    /// removed. Returns the number of removed usages.
+    ///
+    /// # Possible errors:
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn prune(
+        &mut self,
+        name: &String,
+        before: &Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<usize, UsageTrackerError> {
+        if let Occupied(mut e) = self.usage_information.entry(name.to_owned()) {
+            let usages = e.get_mut();
+
+            let removed = match before {
+                Some(before) => usages.prune(*before),
+                None => {
+                    let removed = usages.entries().len();
+                    usages.clear();
+                    removed
+                }
+            };
+
+            if removed > 0 {
+                self.dirty = true;
+            }
+            return Ok(removed);
+        } else {
+            return Err(UsageTrackerError::ObjectNotTracked {
+                name: name.to_owned(),
+            });
+        }
+    }
+
+    /// Removes usages from all objects, like `prune` does for a single object.
+    ///
+    /// Returns the number of removed usages of every object that lost at least one usage.
+    pub fn prune_all(
+        &mut self,
+        before: &Option<chrono::DateTime<chrono::Utc>>,
+    ) -> BTreeMap<String, usize> {
+        let mut report = BTreeMap::new();
+
+        for (name, usages) in self.usage_information.iter_mut() {
+            let removed = match before {
+                Some(before) => usages.prune(*before),
+                None => {
+                    let removed = usages.entries().len();
+                    usages.clear();
+                    removed
+                }
+            };
+
+            if removed > 0 {
+                report.insert(name.to_owned(), removed);
+            }
+        }
+
+        if !report.is_empty() {
+            self.dirty = true;
+        }
+        report
+    }
+
+    /// Calculates how many usages `prune` would remove from an object, without removing them.
+    ///
+    /// # Possible errors:
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn prune_preview(
+        &self,
+        name: &String,
+        before: &Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<usize, UsageTrackerError> {
+        let usages = self.usages(name)?.entries();
+
+        Ok(match before {
+            Some(before) => usages.iter().filter(|u| u.at() < before).count(),
+            None => usages.len(),
+        })
+    }
+
+    /// Provides the names of all objects together with their last usage, most recently used first.
+    ///
+    /// Objects that have never been used come last. Objects with the same last usage are ordered by
+    /// name. If `limit` is set, at most that many objects are returned.
+    pub fn recently_used(&self, limit: Option<usize>) -> Vec<(&String, Option<&DateTime<Utc>>)> {
+        let mut objects: Vec<_> = self
+            .usage_information
+            .iter()
+            .map(|(k, v)| (k, v.last()))
+            .collect();
+
+        // `None` is smaller than `Some`, so reversing the order puts never used objects last
+        objects.sort_by(|(ka, la), (kb, lb)| lb.cmp(la).then_with(|| ka.cmp(kb)));
+        if let Some(limit) = limit {
+            objects.truncate(limit);
+        }
+
+        objects
+    }
+
+    /// Records a new usage of an object.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn record_use(&mut self, name: &str, add_if_new: bool) -> Result<(), UsageTrackerError> {
+        self.record_use_with_precision(name, add_if_new, Precision::default())
+    }
+
+    /// Records a new usage of an object.
+    ///
+    /// The timestamp of the new usage is truncated to the specified `precision`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn record_use_with_precision(
+        &mut self,
+        name: &str,
+        add_if_new: bool,
+        precision: Precision,
+    ) -> Result<(), UsageTrackerError> {
+        self.record_use_from(name, None, add_if_new, precision)
+    }
+
+    /// Records a new usage of an object, but only if it starts a new session.
+    ///
+    /// A new session starts if the last usage is at least `idle` ago. Objects without usages
+    /// (including new objects if `add_if_new` is set) always start a new session. Returns whether
+    /// the usage was recorded.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn record_use_if_idle(
+        &mut self,
+        name: &String,
+        idle: Duration,
+        add_if_new: bool,
+    ) -> Result<bool, UsageTrackerError> {
+        let now = self.clock.now();
+        self.record_use_at_if_idle(name, now, None, idle, add_if_new)
+    }
+
+    /// Records a usage of an object at `timestamp`, that came from `source`, but only if it starts
+    /// a new session.
+    ///
+    /// Works like `record_use_if_idle()`, but the last usage must be at least `idle` before
+    /// `timestamp` instead of now.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn record_use_at_if_idle(
+        &mut self,
+        name: &String,
+        timestamp: DateTime<Utc>,
+        source: Option<String>,
+        idle: Duration,
+        add_if_new: bool,
+    ) -> Result<bool, UsageTrackerError> {
+        if let Some(last) = self.usages(name).ok().and_then(|u| u.last()) {
+            if timestamp - *last < idle {
+                return Ok(false);
+            }
+        }
+
+        self.record_use_at(name, timestamp, source, add_if_new)?;
+        Ok(true)
+    }
+
+    /// Records a usage of an object at a specific point in time, that came from `source`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn record_use_at(
+        &mut self,
+        name: &str,
+        timestamp: DateTime<Utc>,
+        source: Option<String>,
+        add_if_new: bool,
+    ) -> Result<(), UsageTrackerError> {
+        let name = self.resolve(name).to_owned();
+        let now = self.clock.now();
+
+        let usages = match self.usage_information.entry(name.clone()) {
+            Occupied(e) if e.get().archived() => {
+                return Err(UsageTrackerError::ObjectArchived { name });
+            }
+            Occupied(e) => e.into_mut(),
+            Vacant(_) if !add_if_new => {
+                return Err(UsageTrackerError::ObjectNotTracked { name });
+            }
+            Vacant(e) => {
+                validate_name(&name)?;
+                e.insert(Usages::new_at(now))
+            }
+        };
+
+        let timestamp = usages.record_usage_from(timestamp, source, Precision::Nanos);
+        self.dirty = true;
+        self.observers.notify_record(&name, timestamp);
+
+        Ok(())
+    }
+
+    /// Records a new usage of an object, that came from `source`.
+    ///
+    /// The timestamp of the new usage is truncated to the specified `precision`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn record_use_from(
+        &mut self,
+        name: &str,
+        source: Option<String>,
+        add_if_new: bool,
+        precision: Precision,
+    ) -> Result<(), UsageTrackerError> {
+        let timestamp = precision.truncate_to(self.clock.now());
+        self.record_use_at(name, timestamp, source, add_if_new)
+    }
+
+    /// Renames an object.
+    ///
+    /// The object is inserted under its new name before the old name is removed, so it can't get
+    /// lost in between.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::AliasShadowsObject`
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectAlreadyTracked`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn rename(&mut self, from: &String, to: &String) -> Result<(), UsageTrackerError> {
+        validate_name(to)?;
+        self.check_not_alias(to)?;
+        self.tracked(from)?;
+
+        if self.usage_information.contains_key(to) {
+            return Err(UsageTrackerError::ObjectAlreadyTracked {
+                name: to.to_owned(),
+            });
+        }
+
+        let usages = self.usage_information[from].clone();
+        self.usage_information.insert(to.to_owned(), usages);
+        self.usage_information.remove(from);
+        self.retarget_aliases(from, Some(to));
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    /// Removes a currently tracked object permanently.
+    pub fn remove(&mut self, name: &String) {
+        if self.usage_information.contains_key(name) {
+            self.usage_information.remove(name);
+            self.retarget_aliases(name, None);
+            self.dirty = true;
+        }
+    }
+
+    /// Removes the usage of an object recorded at exactly `at`, e.g. to correct a mistake.
+    ///
+    /// If several usages share the timestamp, only one of them is removed, unless `all` is set.
+    /// Returns whether a usage was found.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn remove_usage_at(
+        &mut self,
+        name: &str,
+        at: DateTime<Utc>,
+        all: bool,
+    ) -> Result<bool, UsageTrackerError> {
+        let name = self.resolve(name).to_owned();
+
+        match self.usage_information.get_mut(&name) {
+            Some(usages) => {
+                let found = usages.remove_at(at, all) > 0;
+                if found {
+                    self.dirty = true;
+                }
+                Ok(found)
+            }
+            None => Err(UsageTrackerError::ObjectNotTracked { name }),
+        }
+    }
+
+    /// Provides the version of the data layout this data is serialized in. See `SCHEMA_VERSION`.
+    pub fn schema_version(&self) -> u32 {
+        SCHEMA_VERSION
+    }
+
+    /// Provides a vector with the names of all objects whose names match `pattern`.
+    pub fn search(&self, pattern: &Regex) -> Vec<&String> {
+        self.usage_information
+            .keys()
+            .filter(|k| pattern.is_match(k))
+            .collect()
+    }
+
+    /// Replaces the clock that is used to determine the current point in time.
+    ///
+    /// By default, the system clock is used. The clock also determines when new objects are
+    /// created.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock + Send + Sync>) {
+        self.clock = ClockHandle::new(clock);
+    }
+
+    /// Specifies whether usages in the future are taken into account for counts and predictions.
+    ///
+    /// Usages can be recorded for any point in time, e.g. as reminders. By default, usages in the
+    /// future are left out, because they would distort the predictions.
+    pub fn set_include_future(&mut self, include_future: bool) {
+        self.include_future = include_future;
+    }
+
+    /// Sets how often an object should be used, or removes its target rate if `None` is passed.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn set_target(
+        &mut self,
+        name: &String,
+        target_rate: Option<(u32, DurationUnit)>,
+    ) -> Result<(), UsageTrackerError> {
+        match self.usage_information.get_mut(name) {
+            Some(usages) => {
+                if usages.target_rate() != target_rate {
+                    usages.set_target_rate(target_rate);
+                    self.dirty = true;
+                }
+                Ok(())
+            }
+            None => Err(UsageTrackerError::ObjectNotTracked {
+                name: name.to_owned(),
+            }),
+        }
+    }
+
+    /// Calculates how often an object was used per `window` lately, as a moving average.
+    ///
+    /// The usages within the last `SMOOTHING_WINDOWS` windows before `now` are counted and divided
+    /// by the number of windows. Like with `count_since()`, usages at the beginning of the earliest
+    /// window are counted, and so are usages at `now`. History starts at the creation time or the
+    /// first usage, whichever is earlier. Windows entirely before that are left out, and a window
+    /// only partially after it only counts with the covered fraction. So young objects aren't
+    /// underestimated.
+    ///
+    /// Usages in the future are only counted if `set_include_future()` enabled that.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InsufficientData`, if `now` isn't after the start of the history
+    /// - `UsageTrackerError::ObjectNeverUsed`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn smoothed_rate(
+        &self,
+        name: &String,
+        window: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<f64, UsageTrackerError> {
+        let usages = self.usages(name)?;
+        let counted = self.counted(usages);
+        let first = match counted.first() {
+            Some(first) => first.at(),
+            None => {
+                return Err(UsageTrackerError::ObjectNeverUsed {
+                    name: name.to_owned(),
+                })
+            }
+        };
+        let start = *first.min(usages.created_at());
+
+        // the windows are contiguous, so only the beginning of the earliest one is needed
+        let mut since = now;
+        let mut covered = 0.0;
+        for _ in 0..SMOOTHING_WINDOWS {
+            if since <= start {
+                break;
+            }
+
+            // a window reaching before the earliest representable time also reaches before start
+            match since.checked_sub_signed(window) {
+                Some(begin) if begin >= start => {
+                    covered += 1.0;
+                    since = begin;
+                }
+                _ => {
+                    covered += (since - start).num_milliseconds() as f64
+                        / window.num_milliseconds() as f64;
+                    since = start;
+                }
+            }
+        }
+
+        if covered <= 0.0 {
+            return Err(UsageTrackerError::InsufficientData {
+                name: name.to_owned(),
+            });
+        }
+
+        let until_now = &counted[..counted.partition_point(|u| *u.at() <= now)];
+        Ok(count_since(until_now, &since) as f64 / covered)
+    }
+
+    /// Provides a vector with the names of all objects that haven't been used within `older_than`.
+    ///
+    /// Objects that have never been used are always considered stale.
+    pub fn stale(&self, older_than: Duration) -> Vec<&String> {
+        self.list_filtered(&ListFilter {
+            archived: true,
+            stale: Some(older_than),
+            ..ListFilter::default()
+        })
+    }
+
+    /// Starts a session of an object, e.g. when an editor is opened.
+    ///
+    /// The session is recorded as a usage at the current point in time, so counts and rates treat
+    /// it like any other usage. It runs until `stop_use()` is called. Returns the start of the
+    /// session.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    /// - `UsageTrackerError::ObjectArchived`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    /// - `UsageTrackerError::SessionAlreadyRunning`
+    pub fn start_use(
+        &mut self,
+        name: &str,
+        add_if_new: bool,
+    ) -> Result<DateTime<Utc>, UsageTrackerError> {
+        let name = self.resolve(name).to_owned();
+        if let Some(usages) = self.usage_information.get(&name) {
+            if usages.running_since().is_some() {
+                return Err(UsageTrackerError::SessionAlreadyRunning { name });
+            }
+        }
+
+        let start = self.clock.now();
+        self.record_use_at(&name, start, None, add_if_new)?;
+        self.usage_information
+            .get_mut(&name)
+            .ok_or(UsageTrackerError::ObjectNotTracked { name })?
+            .start_session(start);
+
+        Ok(start)
+    }
+
+    /// Stops the running session of an object, e.g. when an editor is closed.
+    ///
+    /// Returns the duration of the session.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    /// - `UsageTrackerError::SessionNotRunning`
+    pub fn stop_use(&mut self, name: &str) -> Result<Duration, UsageTrackerError> {
+        let name = self.resolve(name).to_owned();
+        let now = self.clock.now();
+        let usages = match self.usage_information.get_mut(&name) {
+            Some(usages) => usages,
+            None => return Err(UsageTrackerError::ObjectNotTracked { name }),
+        };
+
+        match usages.stop_session(now) {
+            Some(duration) => {
+                self.dirty = true;
+                Ok(duration)
+            }
+            None => Err(UsageTrackerError::SessionNotRunning { name }),
+        }
+    }
+
+    /// Provides an owned copy of all stored usages, independent of the internal data structures.
+    pub fn to_map(&self) -> BTreeMap<String, Vec<DateTime<Utc>>> {
+        self.usage_information
+            .iter()
+            .map(|(k, v)| (k.to_owned(), v.list()))
+            .collect()
+    }
+
+    /// Provides how long an object was used in total, by summing up the durations of its sessions.
+    ///
+    /// A session that is still running counts until now. Usages that didn't start a session don't
+    /// count.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn total_time(&self, name: &String) -> Result<Duration, UsageTrackerError> {
+        Ok(self.usages(name)?.total_time(self.clock.now()))
+    }
+
+    /// Provides the number of usages of all objects combined.
+    pub fn total_usages(&self) -> usize {
+        self.usage_information
+            .values()
+            .map(|v| v.entries().len())
+            .sum()
+    }
+
+    /// Makes sure an object is tracked, optionally with a known creation time.
+    ///
+    /// If the object isn't tracked yet, it is added. Its creation time is set to `at`, or now if
+    /// `at` is `None`. If the object (or the object an alias points to) is already tracked, its
+    /// creation time is only changed if `at` is earlier than it. A later `at` never moves the
+    /// creation time forward, because usages may already have been recorded.
+    ///
+    /// Returns whether the object was added.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InvalidName`
+    pub fn touch(
+        &mut self,
+        name: &str,
+        at: Option<DateTime<Utc>>,
+    ) -> Result<bool, UsageTrackerError> {
+        let name = self.resolve(name).to_owned();
+
+        match self.usage_information.get_mut(&name) {
+            Some(usages) => {
+                if let Some(at) = at {
+                    if at < *usages.created_at() {
+                        usages.set_created_at(at);
+                        self.dirty = true;
+                    }
+                }
+                Ok(false)
+            }
+            None => {
+                validate_name(&name)?;
+
+                let usages = Usages::new_at(at.unwrap_or_else(|| self.clock.now()));
+                self.usage_information.insert(name, usages);
+                self.dirty = true;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Removes an alias. The object it points to isn't changed.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::AliasNotDefined`
+    pub fn unalias(&mut self, alias: &String) -> Result<(), UsageTrackerError> {
+        match self.aliases.remove(alias) {
+            Some(_) => {
+                self.dirty = true;
+                Ok(())
+            }
+            None => Err(UsageTrackerError::AliasNotDefined {
+                alias: alias.to_owned(),
+            }),
+        }
+    }
+
+    /// Unarchives an object.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn unarchive(&mut self, name: &String) -> Result<(), UsageTrackerError> {
+        self.set_archived(name, false)
+    }
+
+    /// Removes `member` from the group `group`, or the whole group if `member` is `None`.
+    ///
+    /// Groups without members are removed. The objects themselves aren't changed.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::GroupNotDefined`
+    pub fn ungroup(
+        &mut self,
+        group: &String,
+        member: Option<&String>,
+    ) -> Result<(), UsageTrackerError> {
+        let members = match self.groups.get_mut(group) {
+            Some(members) => members,
+            None => {
+                return Err(UsageTrackerError::GroupNotDefined {
+                    group: group.to_owned(),
+                })
+            }
+        };
+
+        let changed = match member {
+            Some(member) => members.remove(member),
+            None => {
+                members.clear();
+                true
+            }
+        };
+        if members.is_empty() {
+            self.groups.remove(group);
+        }
+        if changed {
+            self.dirty = true;
+        }
+
+        Ok(())
+    }
+
+    /// Calculates the number of usages of the specified object within the specified amount of time.
+    ///
+    /// This works by calculating how much the specified time frame is in comparison to the time
+    /// since the oldest recorded usage. This relationship is the multiplied by the number of total
+    /// uses, to calculate a specific number.
+    ///
+    /// A single usage doesn't say anything about how often an object is used, so predictions
+    /// require at least two usages, and some time must have passed since the first one.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InsufficientData`
+    /// - `UsageTrackerError::ObjectNeverUsed`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn usage(&self, name: &String, time_frame: &Duration) -> Result<f64, UsageTrackerError> {
+        let ui = self.counted(self.usages(name)?);
+        if ui.is_empty() {
+            return Err(UsageTrackerError::ObjectNeverUsed {
+                name: name.to_owned(),
+            });
+        }
+
+        // milliseconds keep short spans accurate, and can't overflow for any chrono duration
+        let time_since_first_use = self.clock.now() - *ui[0].at();
+        if ui.len() == 1 || time_since_first_use.num_milliseconds() <= 0 {
+            return Err(UsageTrackerError::InsufficientData {
+                name: name.to_owned(),
+            });
+        }
+
+        let percentage_of_time_since_first_use =
+            time_frame.num_milliseconds() as f64 / time_since_first_use.num_milliseconds() as f64;
+
+        Ok(percentage_of_time_since_first_use * ui.len() as f64)
+    }
+
+    /// Calculates the number of usages of the specified object within the specified amount of time,
+    /// based only on the usages within the last `reference` period.
+    ///
+    /// Unlike `usage()`, this ignores old usages, so the prediction follows recent changes in how
+    /// often an object is used. If the reference period contains fewer than two usages, this falls
+    /// back to `usage()`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InsufficientData`
+    /// - `UsageTrackerError::ObjectNeverUsed`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn usage_over(
+        &self,
+        name: &String,
+        time_frame: &Duration,
+        reference: &Duration,
+    ) -> Result<f64, UsageTrackerError> {
+        let count = count_since(
+            self.counted(self.usages(name)?),
+            &(self.clock.now() - *reference),
+        );
+        if count < 2 || reference.num_milliseconds() <= 0 {
+            return self.usage(name, time_frame);
+        }
+
+        let percentage_of_reference =
+            time_frame.num_milliseconds() as f64 / reference.num_milliseconds() as f64;
+        Ok(percentage_of_reference * count as f64)
+    }
+
+    /// Calculates the number of usages of the specified object within the specified amount of time,
+    /// taking into account whether it is used more or less often over time.
+    ///
+    /// The model assumes that the rate of usages changes linearly over time. So the number of
+    /// usages up to a point in time is fitted with a quadratic function, using least squares over
+    /// all usages. The prediction is the growth of that function between now and the end of the
+    /// time frame. An increasing rate therefore predicts more usages than `usage()`, a decreasing
+    /// one fewer. The prediction is never negative, even if the usages are dying down.
+    ///
+    /// With fewer than `MIN_TREND_USAGES` usages a trend isn't meaningful, so this falls back to
+    /// `usage()`.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::InsufficientData`
+    /// - `UsageTrackerError::ObjectNeverUsed`
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn usage_trend(
+        &self,
+        name: &String,
+        time_frame: &Duration,
+    ) -> Result<f64, UsageTrackerError> {
+        let ui = self.counted(self.usages(name)?);
+        if ui.len() < MIN_TREND_USAGES {
+            return self.usage(name, time_frame);
+        }
+
+        // scale time so that history spans 0 to 1, which keeps the sums well conditioned
+        let span = (self.clock.now() - *ui[0].at()).num_milliseconds() as f64;
+        if span <= 0.0 {
+            return self.usage(name, time_frame);
+        }
+        let points: Vec<(f64, f64)> = ui
+            .iter()
+            .enumerate()
+            .map(|(i, u)| {
+                (
+                    (*u.at() - *ui[0].at()).num_milliseconds() as f64 / span,
+                    (i + 1) as f64,
+                )
+            })
+            .collect();
+
+        // normal equations of y = a + b * x + c * x^2
+        let mut s = [0.0; 5];
+        let mut t = [0.0; 3];
+        for (x, y) in &points {
+            for (k, sum) in s.iter_mut().enumerate() {
+                *sum += x.powi(k as i32);
+            }
+            for (k, sum) in t.iter_mut().enumerate() {
+                *sum += y * x.powi(k as i32);
+            }
+        }
+        let det = |m: [[f64; 3]; 3]| {
+            m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+                + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+        };
+        let matrix = [[s[0], s[1], s[2]], [s[1], s[2], s[3]], [s[2], s[3], s[4]]];
+        let d = det(matrix);
+        if d.abs() < f64::EPSILON {
+            // all usages at (almost) the same time don't show a trend
+            return self.usage(name, time_frame);
+        }
+
+        // Cramer's rule, the constant term isn't needed for the growth
+        let mut for_b = matrix;
+        let mut for_c = matrix;
+        for (row, value) in t.iter().enumerate() {
+            for_b[row][1] = *value;
+            for_c[row][2] = *value;
+        }
+        let b = det(for_b) / d;
+        let c = det(for_c) / d;
+
+        let end = 1.0 + time_frame.num_milliseconds() as f64 / span;
+        let growth = b * (end - 1.0) + c * (end * end - 1.0);
+        Ok(growth.max(0.0))
+    }
+
+    /// Provides the usages for a specific object. If `name` is an alias, it is resolved first.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    pub fn usages(&self, name: &String) -> Result<&Usages, UsageTrackerError> {
+        self.tracked(self.resolve(name))
+    }
+
+    /// Checks that `name` isn't used as an alias.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::AliasShadowsObject`
+    fn check_not_alias(&self, name: &String) -> Result<(), UsageTrackerError> {
+        match self.aliases.contains_key(name) {
+            true => Err(UsageTrackerError::AliasShadowsObject {
+                name: name.to_owned(),
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Provides the name of the object `name` refers to. That is the target if `name` is an alias,
+    /// and `name` itself otherwise.
+    fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    /// Changes all aliases pointing to `from` to point to `to` instead, and replaces `from` by `to`
+    /// in all groups. If `to` is `None`, those aliases are removed, and so is `from` from all
+    /// groups. Groups without members are removed, like with `ungroup()`.
+    fn retarget_aliases(&mut self, from: &String, to: Option<&String>) {
+        match to {
+            Some(to) => self
+                .aliases
+                .values_mut()
+                .filter(|t| **t == *from)
+                .for_each(|t| *t = to.to_owned()),
+            None => self.aliases.retain(|_, t| *t != *from),
+        }
+
+        for members in self.groups.values_mut() {
+            if members.remove(from) {
+                if let Some(to) = to {
+                    members.insert(to.to_owned());
+                }
+            }
+        }
+        self.groups.retain(|_, members| !members.is_empty());
+    }
+
+    /// Archives or unarchives an object.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    fn set_archived(&mut self, name: &String, archived: bool) -> Result<(), UsageTrackerError> {
+        match self.usage_information.get_mut(name) {
+            Some(usages) => {
+                if usages.archived() != archived {
+                    usages.set_archived(archived);
+                    self.dirty = true;
+                }
+                Ok(())
+            }
+            None => Err(UsageTrackerError::ObjectNotTracked {
+                name: name.to_owned(),
+            }),
+        }
+    }
+
+    /// Provides the usages of `usages`, that are taken into account for counts and predictions.
+    ///
+    /// Unless future usages are included, usages after the current point in time are left out.
+    fn counted<'a>(&self, usages: &'a Usages) -> &'a [Usage] {
+        let entries = usages.entries();
+        match self.include_future {
+            true => entries,
+            false => {
+                let now = self.clock.now();
+                &entries[..entries.partition_point(|u| *u.at() <= now)]
+            }
+        }
+    }
+
+    /// Provides the usages for a specific object, without resolving aliases.
+    ///
+    /// # Possible errors
+    /// - `UsageTrackerError::ObjectNotTracked`
+    fn tracked(&self, name: &str) -> Result<&Usages, UsageTrackerError> {
+        self.usage_information
+            .get(name)
+            .ok_or_else(|| UsageTrackerError::ObjectNotTracked {
+                name: name.to_owned(),
+            })
+    }
+}
+
+/// Provides a stable pseudonym for `name`, made from a short hash of it.
+///
+/// The hash is FNV-1a, so the same name gets the same pseudonym on every machine and with every
+/// version of this crate.
+fn anonymize_name(name: &str) -> String {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("obj_{:012x}", hash >> 16)
+}
+
+/// Provides the number of `usages` at or after `since`. The usages must be in chronological order.
+fn count_since(usages: &[Usage], since: &DateTime<Utc>) -> usize {
+    usages.len() - usages.partition_point(|u| u.at() < since)
+}
+
+/// Checks whether `name` is allowed as an object name.
+///
+/// Names must contain at least one non-whitespace character and must not contain control
+/// characters. Apart from that, all unicode characters are allowed.
+///
+/// # Possible errors
+/// - `UsageTrackerError::InvalidName`
+fn validate_name(name: &str) -> Result<(), UsageTrackerError> {
+    let reason = if name.trim().is_empty() {
+        "name is empty or only consists of whitespace"
+    } else if name.chars().any(char::is_control) {
+        "name contains control characters"
+    } else {
+        return Ok(());
+    };
+
+    Err(UsageTrackerError::InvalidName {
+        name: name.to_owned(),
+        reason: reason.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_name_rejects_empty_and_whitespace_names() {
+        for name in ["", " ", "\t ", "\u{3000}"] {
+            assert!(
+                matches!(
+                    validate_name(name),
+                    Err(UsageTrackerError::InvalidName { .. })
+                ),
+                "{:?} was accepted",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn validate_name_rejects_control_characters() {
+        for name in ["milk\n", "mi\rlk", "milk\u{7}", "\u{1b}[31mmilk"] {
+            assert!(
+                matches!(
+                    validate_name(name),
+                    Err(UsageTrackerError::InvalidName { .. })
+                ),
+                "{:?} was accepted",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn validate_name_accepts_unicode_names() {
+        for name in ["milk", "two words", " padded ", "Müsli", "牛奶", "🥛"] {
+            assert!(validate_name(name).is_ok(), "{:?} was rejected", name);
+        }
+    }
+
+    /// Data as written by v0.1, a map from object names to their usages.
+    const V01_FIXTURE: &str = r#"{
+        "milk": (usages: ["2021-01-02T08:00:00Z", "2021-01-01T08:00:00Z"]),
+        "bread": (usages: []),
+    }"#;
+
+    #[test]
+    #[allow(deprecated)]
+    fn v01_ron_files_are_converted() {
+        let info =
+            UsageInformation::load_usage_information_from_ron_file(V01_FIXTURE.as_bytes()).unwrap();
+        assert_eq!(info.list(), vec!["bread", "milk"]);
+
+        let milk = info.usages(&"milk".to_owned()).unwrap();
+        let first = Utc.ymd(2021, 1, 1).and_hms(8, 0, 0);
+        assert_eq!(
+            milk.list(),
+            vec![first, Utc.ymd(2021, 1, 2).and_hms(8, 0, 0)]
+        );
+        assert_eq!(milk.created_at(), &first);
+
+        let bread = info.usages(&"bread".to_owned()).unwrap();
+        assert!(bread.list().is_empty());
+        assert_eq!(bread.created_at(), &Utc.timestamp(0, 0));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn v01_values_are_converted_like_files() {
+        let value: ron::Value = ron::from_str(V01_FIXTURE).unwrap();
+        let from_value = UsageInformation::from_v01(value).unwrap();
+        let from_file =
+            UsageInformation::load_usage_information_from_ron_file(V01_FIXTURE.as_bytes()).unwrap();
+
+        assert_eq!(from_value, from_file);
+        assert!(!from_value.is_dirty());
+    }
+
+    #[test]
+    fn copy_gives_independent_histories() {
+        let milk = "milk".to_owned();
+        let oat_milk = "oat milk".to_owned();
+        let first = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let second = Utc.ymd(2021, 1, 2).and_hms(12, 0, 0);
+
+        let mut info = UsageInformation::new();
+        info.record_use_at(&milk, first, None, true).unwrap();
+        info.copy(&milk, &oat_milk).unwrap();
+
+        info.record_use_at(&milk, second, None, false).unwrap();
+        assert_eq!(info.usages(&milk).unwrap().list(), vec![first, second]);
+        assert_eq!(info.usages(&oat_milk).unwrap().list(), vec![first]);
+
+        info.remove_usage_at(&oat_milk, first, true).unwrap();
+        assert_eq!(info.usages(&milk).unwrap().list(), vec![first, second]);
+        assert!(info.usages(&oat_milk).unwrap().list().is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_all_usages_and_removes_the_merged_object() {
+        let milk = "milk".to_owned();
+        let oat_milk = "oat milk".to_owned();
+        let alias = "moo juice".to_owned();
+        let earlier = Utc.ymd(2021, 1, 1).and_hms(8, 0, 0);
+        let later = Utc.ymd(2021, 1, 2).and_hms(8, 0, 0);
+        let mut info = UsageInformation::new();
+        info.record_use_at(&milk, later, None, true).unwrap();
+        info.record_use_at(&oat_milk, earlier, None, true).unwrap();
+        info.record_use_at(&oat_milk, later, None, true).unwrap();
+        info.alias(&alias, &oat_milk).unwrap();
+        let total = info.total_usages();
+
+        info.merge(&oat_milk, &milk).unwrap();
+        assert_eq!(info.list(), vec![&milk]);
+        assert_eq!(
+            info.usages(&milk).unwrap().list(),
+            vec![earlier, later, later]
+        );
+        assert_eq!(info.total_usages(), total);
+        assert_eq!(info.aliases().get(&alias), Some(&milk));
+    }
+
+    #[test]
+    fn groups_follow_renamed_merged_and_removed_objects() {
+        let milk = "milk".to_owned();
+        let oat_milk = "oat milk".to_owned();
+        let bread = "bread".to_owned();
+        let drinks = "drinks".to_owned();
+        let groceries = "groceries".to_owned();
+        let members =
+            |names: &[&str]| -> BTreeSet<String> { names.iter().map(|m| m.to_string()).collect() };
+        let mut info = UsageInformation::new();
+        for name in [&milk, &oat_milk, &bread] {
+            info.add(name).unwrap();
+        }
+        info.group(&drinks, &milk).unwrap();
+        info.group(&drinks, &oat_milk).unwrap();
+        info.group(&groceries, &bread).unwrap();
+
+        info.rename(&milk, &"cow milk".to_owned()).unwrap();
+        info.merge(&oat_milk, &bread).unwrap();
+        assert_eq!(info.groups()[&drinks], members(&["bread", "cow milk"]));
+        assert_eq!(info.groups()[&groceries], members(&["bread"]));
+
+        info.remove(&bread);
+        assert_eq!(info.groups()[&drinks], members(&["cow milk"]));
+        assert!(!info.groups().contains_key(&groceries));
+    }
+
+    #[test]
+    fn failed_merges_and_renames_change_nothing() {
+        let milk = "milk".to_owned();
+        let bread = "bread".to_owned();
+        let mut info = UsageInformation::new();
+        info.record_use(&milk, true).unwrap();
+        info.record_use(&bread, true).unwrap();
+        info.mark_clean();
+        let before = info.clone();
+
+        assert!(info.merge(&milk, &"cheese".to_owned()).is_err());
+        assert!(info.merge(&"cheese".to_owned(), &milk).is_err());
+        assert!(info.rename(&milk, &bread).is_err());
+        assert!(info
+            .rename(&"cheese".to_owned(), &"eggs".to_owned())
+            .is_err());
+        assert_eq!(info, before);
+        assert!(!info.is_dirty());
+    }
+
+    #[test]
+    fn rename_moves_the_whole_object() {
+        let milk = "milk".to_owned();
+        let oat_milk = "oat milk".to_owned();
+        let alias = "moo juice".to_owned();
+        let mut info = UsageInformation::new();
+        info.record_use_at(&milk, Utc.ymd(2021, 1, 1).and_hms(8, 0, 0), None, true)
+            .unwrap();
+        info.set_target(&milk, Some((5, DurationUnit::Week)))
+            .unwrap();
+        info.alias(&alias, &milk).unwrap();
+        let usages = info.usages(&milk).unwrap().clone();
+
+        info.rename(&milk, &oat_milk).unwrap();
+        assert_eq!(info.list(), vec![&oat_milk]);
+        assert_eq!(info.usages(&oat_milk).unwrap(), &usages);
+        assert_eq!(info.aliases().get(&alias), Some(&oat_milk));
+    }
+
+    #[test]
+    fn merge_from_skips_objects_named_like_aliases() {
+        let milk = "milk".to_owned();
+        let alias = "moo juice".to_owned();
+        let mut info = UsageInformation::new();
+        info.add(&milk).unwrap();
+        info.alias(&alias, &milk).unwrap();
+
+        let mut other = UsageInformation::new();
+        other.add(&alias).unwrap();
+        other.add(&"bread".to_owned()).unwrap();
+
+        assert_eq!(info.merge_from(other), vec![alias.clone()]);
+        assert_eq!(info.list(), vec!["bread", "milk"]);
+        assert_eq!(info.aliases().get(&alias), Some(&milk));
+    }
+
+    #[test]
+    fn recording_untracked_objects_requires_adding_them() {
+        let mut info = UsageInformation::new();
+        assert!(matches!(
+            info.record_use("milk", false),
+            Err(UsageTrackerError::ObjectNotTracked { name }) if name == "milk"
+        ));
+        assert!(info.list().is_empty());
+        assert!(!info.is_dirty());
+
+        info.record_use("milk", true).unwrap();
+        info.record_use("milk", false).unwrap();
+        assert_eq!(info.count(&"milk".to_owned()).unwrap(), 2);
+    }
+
+    #[test]
+    fn usage_needs_at_least_two_usages() {
+        let milk = "milk".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(0, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        info.add(&milk).unwrap();
+        assert!(matches!(
+            info.usage(&milk, &Duration::days(1)),
+            Err(UsageTrackerError::ObjectNeverUsed { .. })
+        ));
+
+        info.record_use_at(&milk, now - Duration::days(4), None, false)
+            .unwrap();
+        assert!(matches!(
+            info.usage(&milk, &Duration::days(1)),
+            Err(UsageTrackerError::InsufficientData { .. })
+        ));
+
+        info.record_use_at(&milk, now - Duration::days(2), None, false)
+            .unwrap();
+        assert_eq!(info.usage(&milk, &Duration::days(1)).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn usage_keeps_sub_second_precision() {
+        let milk = "milk".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(0, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        let first = now - Duration::milliseconds(1500);
+        for offset in [0, 400, 900] {
+            info.record_use_at(&milk, first + Duration::milliseconds(offset), None, true)
+                .unwrap();
+        }
+
+        let time_frame = Duration::milliseconds(250);
+        let reference = time_frame.num_nanoseconds().unwrap() as f64
+            / (now - first).num_nanoseconds().unwrap() as f64
+            * 3.0;
+        let usage = info.usage(&milk, &time_frame).unwrap();
+        assert!(
+            (usage - reference).abs() < 1e-9,
+            "{} != {}",
+            usage,
+            reference
+        );
+    }
+
+    #[test]
+    fn usage_needs_time_to_pass_since_the_first_usage() {
+        let milk = "milk".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(0, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        info.record_use_at(&milk, now, None, true).unwrap();
+        info.record_use_at(&milk, now, None, true).unwrap();
+        assert!(matches!(
+            info.usage(&milk, &Duration::days(1)),
+            Err(UsageTrackerError::InsufficientData { .. })
+        ));
+    }
+
+    #[test]
+    fn future_usages_are_only_counted_if_included() {
+        let milk = "milk".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(12, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        info.record_use_at(&milk, now - Duration::hours(4), None, true)
+            .unwrap();
+        info.record_use_at(&milk, now + Duration::hours(8), None, true)
+            .unwrap();
+
+        let starts = [6, 12, 18, 22];
+        assert_eq!(info.counted_usages(&milk).unwrap().len(), 1);
+        assert_eq!(
+            info.count_per_segment(&milk, &starts, &Utc).unwrap(),
+            vec![1, 0, 0, 0]
+        );
+
+        info.set_include_future(true);
+        assert_eq!(info.counted_usages(&milk).unwrap().len(), 2);
+        assert_eq!(
+            info.count_per_segment(&milk, &starts, &Utc).unwrap(),
+            vec![1, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn smoothed_rate_counts_like_count_since() {
+        let milk = "milk".to_owned();
+        let created = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let now = created + Duration::days(10);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(created)));
+        info.add(&milk).unwrap();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        let window = Duration::days(1);
+        let since = now - window * SMOOTHING_WINDOWS;
+        for at in [since - Duration::seconds(1), since, now - window, now] {
+            info.record_use_at(&milk, at, None, false).unwrap();
+        }
+
+        assert_eq!(info.count_since(&milk, &since).unwrap(), 3);
+        assert_eq!(
+            info.smoothed_rate(&milk, window, now).unwrap(),
+            3.0 / SMOOTHING_WINDOWS as f64
+        );
+    }
+
+    #[test]
+    fn smoothed_rate_handles_extreme_windows_and_empty_history() {
+        let milk = "milk".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(0, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now - Duration::days(2))));
+        info.record_use(&milk, true).unwrap();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        // the window reaches before the earliest representable time, but is only partially covered
+        let huge = Duration::max_value();
+        let rate = info.smoothed_rate(&milk, huge, now).unwrap();
+        assert!(rate.is_finite() && rate > 0.0, "{}", rate);
+
+        assert!(matches!(
+            info.smoothed_rate(&milk, Duration::days(1), now - Duration::days(3)),
+            Err(UsageTrackerError::InsufficientData { .. })
+        ));
+    }
+
+    #[test]
+    fn next_use_only_includes_future_usages_if_enabled() {
+        let milk = "milk".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(0, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+        for days in [-2, -1, 2] {
+            info.record_use_at(&milk, now + Duration::days(days), None, true)
+                .unwrap();
+        }
+
+        assert_eq!(info.predict_next_use(&milk, now).unwrap(), Some(now));
+
+        info.set_include_future(true);
+        assert_eq!(
+            info.predict_next_use(&milk, now).unwrap(),
+            Some(now + Duration::days(4))
+        );
+    }
+
+    #[test]
+    fn predictions_resolve_aliases() {
+        let milk = "milk".to_owned();
+        let alias = "moo juice".to_owned();
+        let now = Utc.ymd(2021, 1, 10).and_hms(0, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        for days in 1..=6 {
+            info.record_use_at(&milk, now - Duration::days(days), None, true)
+                .unwrap();
+        }
+        info.alias(&alias, &milk).unwrap();
+
+        let day = Duration::days(1);
+        let week = Duration::weeks(1);
+        assert_eq!(
+            info.usage(&alias, &day).unwrap(),
+            info.usage(&milk, &day).unwrap()
+        );
+        assert_eq!(
+            info.usage_over(&alias, &day, &week).unwrap(),
+            info.usage_over(&milk, &day, &week).unwrap()
+        );
+        assert_eq!(
+            info.usage_trend(&alias, &day).unwrap(),
+            info.usage_trend(&milk, &day).unwrap()
+        );
+    }
+
+    #[test]
+    fn json_output_is_sorted_and_stable() {
+        let json = r#"{"usage_information":{
+            "milk":{"usages":["2021-01-02T00:00:00Z","2021-01-01T00:00:00Z"],
+                "created_at":"2021-01-01T00:00:00Z","archived":false},
+            "bread":{"usages":[],"created_at":"2021-01-01T00:00:00Z","archived":false}
+        }}"#;
+        let info: UsageInformation = serde_json::from_str(json).unwrap();
+        let output = serde_json::to_string(&info).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"usage_information":{"#,
+                r#""bread":{"usages":[],"created_at":"2021-01-01T00:00:00Z","archived":false},"#,
+                r#""milk":{"usages":["2021-01-01T00:00:00Z","2021-01-02T00:00:00Z"],"#,
+                r#""created_at":"2021-01-01T00:00:00Z","archived":false}}}"#
+            )
+        );
+
+        let reloaded: UsageInformation = serde_json::from_str(&output).unwrap();
+        assert_eq!(serde_json::to_string(&reloaded).unwrap(), output);
+    }
+
+    #[test]
+    fn anonymized_data_reveals_no_names_or_sources() {
+        let at = Utc.ymd(2021, 1, 1).and_hms(8, 0, 0);
+        let mut info = UsageInformation::new();
+        info.record_use_at("milk", at, Some("fridge".to_owned()), true)
+            .unwrap();
+        info.record_use_at("milk", at, None, true).unwrap();
+
+        let (anonymized, mapping) = info.anonymized();
+        let json = serde_json::to_string(&anonymized).unwrap();
+        assert!(
+            !json.contains("milk") && !json.contains("fridge"),
+            "{}",
+            json
+        );
+
+        let usages = anonymized.usages(&mapping["milk"]).unwrap();
+        let sources: Vec<_> = usages.entries().iter().map(Usage::source).collect();
+        assert_eq!(sources, vec![Some(&mapping["fridge"]), None]);
+        assert_eq!(usages.list(), vec![at, at]);
+    }
+
+    #[test]
+    fn stale_handles_durations_longer_than_history() {
+        let now = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+        info.record_use("milk", true).unwrap();
+        info.add(&"bread".to_owned()).unwrap();
+
+        let older_than = DurationUnit::Day.checked_duration(100_000_000).unwrap();
+        assert_eq!(info.stale(older_than), vec!["bread"]);
+    }
+
+    #[test]
+    fn objects_are_created_according_to_the_clock() {
+        let now = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+
+        info.add(&"milk".to_owned()).unwrap();
+        info.record_use("bread", true).unwrap();
+        info.touch("cheese", None).unwrap();
+
+        for name in ["milk", "bread", "cheese"] {
+            let usages = info.usages(&name.to_owned()).unwrap();
+            assert_eq!(usages.created_at(), &now, "{}", name);
+        }
+    }
+
+    #[test]
+    fn recently_used_puts_never_used_objects_last() {
+        let earlier = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let later = Utc.ymd(2021, 1, 2).and_hms(12, 0, 0);
+        let mut info = UsageInformation::new();
+
+        info.add(&"cheese".to_owned()).unwrap();
+        info.add(&"apples".to_owned()).unwrap();
+        info.record_use_at("milk", earlier, None, true).unwrap();
+        info.record_use_at("bread", earlier, None, true).unwrap();
+        info.record_use_at("eggs", later, None, true).unwrap();
+
+        assert_eq!(
+            info.recently_used(None),
+            vec![
+                (&"eggs".to_owned(), Some(&later)),
+                (&"bread".to_owned(), Some(&earlier)),
+                (&"milk".to_owned(), Some(&earlier)),
+                (&"apples".to_owned(), None),
+                (&"cheese".to_owned(), None),
+            ]
+        );
+        assert_eq!(
+            info.recently_used(Some(2)),
+            vec![
+                (&"eggs".to_owned(), Some(&later)),
+                (&"bread".to_owned(), Some(&earlier)),
+            ]
+        );
+    }
+
+    #[test]
+    fn debug_output_does_not_read_the_clock() {
+        struct UnreadableClock;
+
+        impl Clock for UnreadableClock {
+            fn now(&self) -> DateTime<Utc> {
+                panic!("clock was read")
+            }
+        }
+
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(UnreadableClock));
+        format!("{:?}", info);
+    }
 
-        Ok(&self.usage_information[name])
+    #[test]
+    fn usage_information_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<UsageInformation>();
     }
 }
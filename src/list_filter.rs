@@ -0,0 +1,61 @@
+use crate::Usages;
+use chrono::{DateTime, Duration, Utc};
+
+/// Conditions objects have to fulfill to be listed by `UsageInformation::list_filtered()`.
+///
+/// Only objects that fulfill all set conditions are listed. The default filter lists all objects,
+/// except archived ones.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListFilter {
+    /// Also list archived objects.
+    pub archived: bool,
+    /// Only list objects with at least this many usages.
+    pub min_count: Option<usize>,
+    /// Only list objects with at most this many usages.
+    pub max_count: Option<usize>,
+    /// Only list objects that haven't been used within this duration. Objects that have never been
+    /// used are always considered stale.
+    pub stale: Option<Duration>,
+}
+
+impl ListFilter {
+    /// Checks whether an object with the provided `usages` fulfills all set conditions at the point
+    /// in time `now`.
+    pub fn matches(&self, usages: &Usages, now: DateTime<Utc>) -> bool {
+        let count = usages.entries().len();
+        let stale = match (self.stale, usages.last()) {
+            (None, _) | (Some(_), None) => true,
+            // nothing can have been used before the earliest representable point in time
+            (Some(stale), Some(last)) => match now.checked_sub_signed(stale) {
+                Some(limit) => *last < limit,
+                None => false,
+            },
+        };
+
+        (self.archived || !usages.archived())
+            && !matches!(self.min_count, Some(min) if count < min)
+            && !matches!(self.max_count, Some(max) if count > max)
+            && stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Precision;
+    use chrono::TimeZone;
+
+    #[test]
+    fn stale_durations_beyond_the_time_range_match_nothing_used() {
+        let now = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let filter = ListFilter {
+            stale: Some(Duration::max_value()),
+            ..ListFilter::default()
+        };
+
+        let mut usages = Usages::new_at(now);
+        assert!(filter.matches(&usages, now));
+        usages.record_usage_at(now, Precision::Nanos);
+        assert!(!filter.matches(&usages, now));
+    }
+}
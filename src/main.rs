@@ -3,16 +3,46 @@ use atty::Stream;
 use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use clap::Parser;
 use human_panic::setup_panic;
+use owo_colors::OwoColorize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use standard_paths::{LocationType, StandardPaths};
 use std::{
     fs::{self, File},
+    io::{self, BufRead, Write},
     path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
 };
 use usage_tracker::*;
 
+mod formats;
+#[cfg(feature = "server")]
+mod server;
+
 const PATH_CONVERT_ERROR: &str =
     "could not convert file name for other error message. WTF have you done?!";
 const JSON_FORMAT_ERROR: &str = "could not serialize JSON output";
+/// The file containing the path of the data file of a project, see `project_file()`.
+const PROJECT_POINTER_FILE: &str = ".usage-tracker";
+/// The data file of a project, see `project_file()`.
+const PROJECT_DATA_FILE: &str = ".usage-tracker.json";
+/// The environment variable disabling colored output, see https://no-color.org.
+const NO_COLOR_VARIABLE: &str = "NO_COLOR";
+/// The environment variable containing the default unit for the `usage` command.
+const DEFAULT_UNIT_VARIABLE: &str = "USAGE_TRACKER_DEFAULT_UNIT";
+/// The highest count a single line of an imported file may have, see `ImportEntry`.
+const MAX_IMPORT_COUNT: i64 = 10_000;
+
+/// Whether warnings are printed as JSON, like errors with `--error-format json`. See `warn()`.
+static JSON_WARNINGS: AtomicBool = AtomicBool::new(false);
+
+/// Appends a line to the output of a command.
+macro_rules! outln {
+    ($out:expr, $($arg:tt)*) => {{
+        $out.push_str(&format!($($arg)*));
+        $out.push('\n');
+    }};
+}
 
 /// The CLI.
 #[derive(Debug, Parser)]
@@ -28,14 +58,319 @@ struct Opt {
     ///
     /// Supported file formats:
     /// - json
+    /// - jsonl (usages of tracked objects are appended instead of rewriting the file)
+    /// - cbor (if built with the `cbor` feature)
+    ///
+    /// If the program was built with the `remote` feature, this can also be a http or https URL.
+    /// Data loaded from a URL is read-only.
+    ///
+    /// If not specified, the closest `.usage-tracker` file (containing the path of the data file)
+    /// or `.usage-tracker.json` file (being the data file) in the current directory or its
+    /// parents is used. If there is none, the default file in the application data directory is
+    /// used.
+    ///
+    /// Takes precedence over `--data-dir`.
     ///
     /// Warning: even if RON support is added at some point, you won't be able to read files
     /// from v0.1 with it, because those files have a different file format.
     #[clap(parse(from_os_str), verbatim_doc_comment)]
     data_file: Option<PathBuf>,
+    /// Use the default files in this directory instead of the application data directory.
+    ///
+    /// The data is loaded from `<dir>/usages.json` (or another supported format) and saved to
+    /// `<dir>/usages.json`. Project files in the current directory and its parents are ignored.
+    #[clap(long, parse(from_os_str))]
+    data_dir: Option<PathBuf>,
     /// If a change is made, don't keep a backup of the original data file.
     #[clap(long)]
     no_backup: bool,
+    /// Write backups in this format instead of keeping the original file, e.g. `cbor`.
+    ///
+    /// The backup is written next to the data file, with `.bak.<format>` appended to its name.
+    /// Supports the same formats as the data file.
+    #[clap(long, conflicts_with = "no-backup")]
+    backup_format: Option<String>,
+    /// Perform the operation in memory, but never save the changes to the data file.
+    #[clap(long)]
+    no_save: bool,
+    /// Don't change anything, only show what would be changed.
+    ///
+    /// For `prune`, the number and date range of the usages that would be removed are shown.
+    /// Other commands are performed in memory, but not saved.
+    #[clap(long)]
+    dry_run: bool,
+    /// Write the output of the command to this file, instead of printing it.
+    #[clap(long, short, parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// The format in which errors and warnings are printed.
+    ///
+    /// Allowed values:
+    /// - text
+    /// - json
+    ///
+    /// If not specified, errors and warnings are printed as JSON if the output is piped, and as
+    /// text otherwise.
+    #[clap(long, parse(try_from_str = parse_error_format), verbatim_doc_comment)]
+    error_format: Option<ErrorFormat>,
+    /// When to color human readable output.
+    ///
+    /// Allowed values:
+    /// - auto: only if printed to a terminal and the NO_COLOR variable isn't set
+    /// - always
+    /// - never
+    ///
+    /// JSON output is never colored.
+    #[clap(
+        long,
+        default_value = "auto",
+        parse(try_from_str = parse_color_choice),
+        verbatim_doc_comment
+    )]
+    color: ColorChoice,
+    /// Add objects that aren't tracked yet when recording a usage, as if `use --add` was passed.
+    #[clap(long, conflicts_with = "no-auto-add")]
+    auto_add: bool,
+    /// Never add objects that aren't tracked yet when recording a usage, even if `use --add` was
+    /// passed.
+    #[clap(long)]
+    no_auto_add: bool,
+    /// Refuse to run commands that change the data, and never write to the data file.
+    ///
+    /// Unlike `--no-save` and `--dry-run`, commands that change the data fail right away.
+    #[clap(long)]
+    read_only: bool,
+    /// When saving, store the usages as seconds since the Unix epoch instead of dates and times.
+    ///
+    /// This roughly halves the size of large data files, but usages are only stored with second
+    /// precision. Both representations are always accepted when loading.
+    #[clap(long)]
+    epoch_seconds: bool,
+    /// Don't ask for confirmation before removing data.
+    ///
+    /// On a terminal, `clear`, `remove` and `prune --all` ask before removing anything. If the
+    /// input is piped, they never ask. `clear` still requires `--i-am-sure` then.
+    #[clap(long, short)]
+    yes: bool,
+    /// Interpret dates and times without a timezone as UTC instead of local time.
+    ///
+    /// This applies to `count --since`, `export --from`, `prune --before`, `touch --at`,
+    /// `unrecord --at` and `use --at`. Days of `heatmap` and `on` are always determined in the local timezone.
+    #[clap(long)]
+    utc: bool,
+}
+
+impl Opt {
+    /// Provides whether objects that aren't tracked yet should be added when recording a usage, if
+    /// the user decided that globally.
+    ///
+    /// If this is `Some`, it overrides the `--add` flag of the command itself.
+    fn auto_add(&self) -> Option<bool> {
+        match (self.auto_add, self.no_auto_add) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// A line of a file imported with the `import` command.
+#[derive(Debug, Deserialize)]
+struct ImportEntry {
+    /// The name of the object that was used.
+    name: String,
+    /// When the object was used.
+    timestamp: DateTime<Utc>,
+    /// How often the object was used at that time.
+    #[serde(default = "default_import_count")]
+    count: i64,
+    /// Where the usages came from.
+    source: Option<String>,
+}
+
+/// Provides the count of an imported line, if it doesn't specify one.
+fn default_import_count() -> i64 {
+    1
+}
+
+/// A usage recorded outside of the data itself.
+///
+/// `use --fast` writes these into the spool directory, which is next to the data file, with
+/// `.events` appended to its name. Every file in it contains one entry per line as a JSON object,
+/// until `compact` adds them to the data. JSON Lines data files contain these entries as well.
+#[derive(Debug, Deserialize, Serialize)]
+struct LogEntry {
+    /// The name of the object that was used.
+    name: String,
+    /// When the object was used.
+    timestamp: DateTime<Utc>,
+    /// Where the usage came from.
+    source: Option<String>,
+    /// Whether the object should be added if it isn't tracked yet.
+    add_if_new: bool,
+}
+
+/// A line of a JSON Lines data file.
+///
+/// A JSON Lines data file optionally starts with a snapshot of the complete data. Usages recorded
+/// later are appended as log entries, so the prior content never has to be rewritten.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum JsonLinesEntry {
+    Use(LogEntry),
+    Snapshot(UsageInformation),
+}
+
+/// The shells for which the `hook` command can generate a snippet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Shell {
+    Bash,
+    Zsh,
+}
+
+/// What happened when recording a usage with the `use` command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum UseOutcome {
+    /// The object was added and the usage recorded.
+    Created,
+    /// The usage was recorded.
+    Recorded,
+    /// The usage wasn't recorded, because it belongs to the current session.
+    Skipped,
+    /// The object was unarchived and the usage recorded.
+    Unarchived,
+}
+
+impl std::fmt::Display for UseOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            UseOutcome::Created => "created",
+            UseOutcome::Recorded => "recorded",
+            UseOutcome::Skipped => "skipped",
+            UseOutcome::Unarchived => "unarchived",
+        })
+    }
+}
+
+impl Commands {
+    /// Specifies whether the command can change the data, or writes data files in general.
+    fn is_mutating(&self) -> bool {
+        match self {
+            Commands::Add { .. }
+            | Commands::Alias { .. }
+            | Commands::Archive { .. }
+            | Commands::BulkAdd { .. }
+            | Commands::Clear { .. }
+            | Commands::Compact
+            | Commands::Copy { .. }
+            | Commands::Dedup { .. }
+            | Commands::Group { .. }
+            | Commands::Import { .. }
+            | Commands::MergeFiles { .. }
+            | Commands::Normalize
+            | Commands::Prune { .. }
+            | Commands::Rebuild
+            | Commands::Remove { .. }
+            | Commands::RenameAll { .. }
+            | Commands::StartUse { .. }
+            | Commands::StopUse { .. }
+            | Commands::Target { .. }
+            | Commands::Touch { .. }
+            | Commands::Unalias { .. }
+            | Commands::Unarchive { .. }
+            | Commands::Ungroup { .. }
+            | Commands::Unrecord { .. }
+            | Commands::Use { .. } => true,
+            Commands::Busiest { .. }
+            | Commands::Correlate { .. }
+            | Commands::Count { .. }
+            | Commands::Export { .. }
+            | Commands::Heatmap { .. }
+            | Commands::Hook { .. }
+            | Commands::Info
+            | Commands::List { .. }
+            | Commands::NextUse { .. }
+            | Commands::On { .. }
+            | Commands::Progress { .. }
+            | Commands::Rate { .. }
+            | Commands::Recent { .. }
+            | Commands::Show { .. }
+            | Commands::Stale { .. }
+            | Commands::Stats { .. }
+            | Commands::Total
+            | Commands::Usage { .. }
+            | Commands::Watch { .. } => false,
+            #[cfg(feature = "server")]
+            Commands::Serve { .. } => false,
+        }
+    }
+}
+
+/// A point in time passed by the user, as parsed by `parse_date()`.
+#[derive(Clone, Copy, Debug)]
+enum DateArg {
+    /// A date and time without a timezone.
+    Naive(NaiveDateTime),
+    /// A date and time with a timezone.
+    Zoned(DateTime<Utc>),
+}
+
+impl DateArg {
+    /// Converts the point in time into UTC.
+    ///
+    /// Dates and times without a timezone are interpreted as UTC if `utc` is set, and as local
+    /// time otherwise.
+    fn resolve(self, utc: bool) -> Result<DateTime<Utc>> {
+        match self {
+            DateArg::Naive(dt) if utc => Ok(DateTime::from_utc(dt, Utc)),
+            DateArg::Naive(dt) => local_to_utc(&dt),
+            DateArg::Zoned(dt) => Ok(dt),
+        }
+    }
+}
+
+/// When human readable output is colored.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ColorChoice {
+    Always,
+    Auto,
+    Never,
+}
+
+impl ColorChoice {
+    /// Decides whether output written to `stream` is colored.
+    ///
+    /// With `Auto`, output is colored if `stream` is a terminal and the `NO_COLOR` variable isn't
+    /// set to a non-empty value.
+    fn enabled(self, stream: Stream) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Auto => {
+                atty::is(stream)
+                    && std::env::var_os(NO_COLOR_VARIABLE)
+                        .unwrap_or_default()
+                        .is_empty()
+            }
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// The formats in which errors can be printed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ErrorFormat {
+    Json,
+    Text,
+}
+
+/// What the `usage` command does if an object has too few usages for a prediction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum OnInsufficient {
+    /// Print the number of usages, without scaling it to the time frame.
+    Count,
+    /// Fail with an error.
+    Error,
+    /// Print 0.
+    Zero,
 }
 
 /// All possible commands.
@@ -48,52 +383,587 @@ enum Commands {
         name: String,
     },
 
+    /// Define an alternative name for an object.
+    ///
+    /// Recording, showing and counting usages of the alias affects the object instead.
+    Alias {
+        /// The alternative name.
+        alias: String,
+        /// The name of the object the alias refers to.
+        target: String,
+    },
+
+    /// Archive an object.
+    ///
+    /// Archived objects keep their usages, but are hidden from `list` and no new usages can be
+    /// recorded.
+    Archive {
+        /// The name of the object to archive.
+        name: String,
+    },
+
+    /// Add all objects listed in a file, e.g. one written by `export --names-only`.
+    ///
+    /// The file contains either a JSON array of names, or one name per line. Objects that are
+    /// already tracked are skipped.
+    BulkAdd {
+        /// The file containing the names.
+        #[clap(parse(from_os_str))]
+        file: PathBuf,
+    },
+
+    /// Show when an object is used most often.
+    Busiest {
+        /// The name of the object.
+        name: String,
+        /// The periods to compare.
+        ///
+        /// Allowed values:
+        /// - hour: the hours of the day
+        /// - weekday: the days of the week
+        /// - month: the months of the year
+        ///
+        /// Periods are determined in the local timezone.
+        #[clap(long, default_value = "hour", parse(try_from_str = parse_granularity), verbatim_doc_comment)]
+        by: Granularity,
+    },
+
     /// Remove **all** objects permanently.
     Clear {
-        /// REQUIRED: confirm you are sure to clear the data store.
+        /// Confirm you are sure to clear the data store.
         ///
         /// This is an additional check to ensure users don't accidentally delete all of their usage
-        /// records.
+        /// records. On a terminal, you are asked instead if this isn't passed. Otherwise it is
+        /// required.
+        #[clap(long = "i-am-sure")]
+        confirmation: bool,
+    },
+
+    /// Rewrite the data file, even if nothing changed.
+    ///
+    /// The usages recorded with `use --fast` are added to the data, then it is serialized again
+    /// and written back to the data file. This normalizes the file and reclaims space after heavy
+    /// pruning.
+    ///
+    /// The files in the spool directory are only deleted after the data file was saved, so a crash
+    /// never loses usages. A crash between saving and deleting them makes the next `compact` add
+    /// those usages again though.
+    ///
+    /// Usages that can't be recorded, e.g. because their object is archived or isn't tracked, are
+    /// skipped with a warning. They are removed together with the other usages, so they don't
+    /// block later runs.
+    Compact,
+
+    /// Copy the usages of an object to a new object.
+    Copy {
+        /// The name of the object to copy.
+        from: String,
+        /// The name of the new object.
+        to: String,
+    },
+
+    /// Show how often two objects are used close to each other.
+    ///
+    /// Counts the usages of the first object, that have a usage of the second object within the
+    /// window before or after them.
+    Correlate {
+        /// The name of the first object.
+        a: String,
+        /// The name of the second object.
+        b: String,
+        /// How close the usages have to be, e.g. `1d` or `30m`.
+        #[clap(long, default_value = "1d", parse(try_from_str = parse_duration))]
+        window: Duration,
+    },
+
+    /// Count the usages of an object.
+    ///
+    /// With `--pattern`, the number of objects whose names match is counted instead.
+    Count {
+        /// The name of the object.
+        #[clap(required_unless_present = "pattern")]
+        name: Option<String>,
+        /// Count the objects whose names match this regular expression, e.g. `^editor-`.
+        #[clap(long, conflicts_with_all = &["name", "since", "include-future"])]
+        pattern: Option<String>,
+        /// Only count usages at or after this point in time.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(short, long, parse(try_from_str = parse_date))]
+        since: Option<DateArg>,
+        /// Also count usages in the future, e.g. ones recorded with `use --at` as reminders.
+        ///
+        /// By default, only usages up to now are counted.
+        #[clap(long)]
+        include_future: bool,
+    },
+
+    /// Merge objects whose names only differ in whitespace.
+    ///
+    /// The usages are merged into the object with the most usages, unless `--into` chooses
+    /// another one.
+    Dedup {
+        /// Also merge objects whose names only differ in case.
+        #[clap(long, short)]
+        case_insensitive: bool,
+        /// Merge the duplicates of this object into it.
+        ///
+        /// The object must have duplicates. Other duplicates are still merged into the object with
+        /// the most usages.
+        #[clap(long)]
+        into: Option<String>,
+    },
+
+    /// Export all data as JSON.
+    ///
+    /// With `--names-only`, only the names of the tracked objects are exported, without any
+    /// usages. This allows setting up the same objects on another machine with `bulk-add`, without
+    /// sharing the usage history.
+    ///
+    /// With `--anonymize`, all names and sources are replaced by stable pseudonyms like
+    /// `obj_3f2a9c01b7d4`, while all timestamps are kept. This allows sharing usage patterns
+    /// without revealing which objects are tracked.
+    ///
+    /// With `--timeline`, all usages of all objects are exported in chronological order instead,
+    /// e.g. for an activity timeline. The output is a JSON array of objects with the fields `at`
+    /// (when the usage happened) and `name` (the object that was used).
+    Export {
+        /// Only export the names of the tracked objects.
+        ///
+        /// The names are printed one per line, or as a JSON array if the output is piped.
+        #[clap(long)]
+        names_only: bool,
+        /// Export all usages as a chronological timeline.
+        #[clap(long, conflicts_with = "names-only")]
+        timeline: bool,
+        /// Only export usages at or after this point in time.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(long, requires = "timeline", parse(try_from_str = parse_date))]
+        from: Option<DateArg>,
+        /// Only export usages at or before this point in time.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(long, requires = "timeline", parse(try_from_str = parse_date))]
+        to: Option<DateArg>,
+        /// Replace the names of objects, aliases and groups and the sources of usages by stable
+        /// pseudonyms.
+        #[clap(long)]
+        anonymize: bool,
+        /// Write which pseudonym belongs to which name to this file, as a JSON object.
+        #[clap(long, requires = "anonymize")]
+        mapping: Option<PathBuf>,
+    },
+
+    /// Add an object to a group, e.g. to show the `stats` of all editors combined.
+    ///
+    /// The group is created if it doesn't exist yet.
+    Group {
+        /// The name of the group.
+        group: String,
+        /// The name of the object to add.
+        member: String,
+    },
+
+    /// Export the number of usages per day of an object, e.g. for a calendar heatmap.
+    ///
+    /// The output is JSON, containing the name of the object, the range and the counts of all days
+    /// within the range. Days are determined in the local timezone.
+    Heatmap {
+        /// The name of the object.
+        name: String,
+        /// The first day to export. Defaults to one year before `--to`.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(long, parse(try_from_str = parse_local_date))]
+        from: Option<DateTime<Utc>>,
+        /// The last day to export. Defaults to today.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(long, parse(try_from_str = parse_local_date))]
+        to: Option<DateTime<Utc>>,
+        /// Export CSV with the columns `date` and `count` instead of JSON. The name of the object
+        /// and the range are written into a leading comment line.
+        #[clap(long)]
+        csv: bool,
+    },
+
+    /// Print a snippet that records every command run in a shell.
+    ///
+    /// The first word of every command is recorded with `use --fast --add`. To enable it, add
+    /// this to your shell configuration:
+    /// - bash: eval "$(usage-tracker hook bash)"
+    /// - zsh: eval "$(usage-tracker hook zsh)"
+    #[clap(verbatim_doc_comment)]
+    Hook {
+        /// The shell to generate the snippet for.
+        ///
+        /// Allowed values:
+        /// - bash
+        /// - zsh
+        #[clap(parse(try_from_str = parse_shell), verbatim_doc_comment)]
+        shell: Shell,
+    },
+
+    /// Import usages from a JSON lines file.
+    ///
+    /// Every line of the file must contain a JSON object with these fields:
+    /// - name: the name of the object that was used
+    /// - timestamp: when the object was used
+    /// - count: optional, how often the object was used at that time, defaults to 1, at most 10000
+    /// - source: optional, where the usage came from
+    ///
+    /// With `--replace`, the file is instead a data file, that replaces all current data.
+    #[clap(verbatim_doc_comment)]
+    Import {
+        /// Add objects that aren't tracked yet.
+        #[clap(long = "add")]
+        add_if_new: bool,
+        /// The file to import.
+        #[clap(parse(from_os_str))]
+        file: PathBuf,
+        /// Replace all current data with the data file `file`, instead of importing usages.
+        ///
+        /// A backup of the current data file is always created, even if `--no-backup` is set.
+        #[clap(long, conflicts_with = "add-if-new")]
+        replace: bool,
+        /// Confirm that the current data should be replaced.
         #[clap(long = "i-am-sure")]
         confirmation: bool,
     },
 
+    /// Show where the data is stored and how much data there is.
+    ///
+    /// This is useful to find out why a data file doesn't load as expected.
+    Info,
+
     /// List all currently tracked objects.
+    ///
+    /// If no objects are listed, this fails. If the output is piped, an empty JSON array is printed
+    /// instead.
+    #[clap(visible_alias = "ls")]
     List {
+        /// Also list archived objects.
+        #[clap(long, short)]
+        all: bool,
+        /// Print each objects share of all recorded usages.
+        #[clap(long, conflicts_with = "verbose")]
+        percent: bool,
         /// Print all usage dates in addition to the objects names.
         #[clap(long, short)]
         verbose: bool,
+        /// Only list objects that haven't been used within this number of days.
+        #[clap(long)]
+        stale: Option<u32>,
+        /// Only list objects with at least this many usages.
+        #[clap(long)]
+        min_count: Option<usize>,
+        /// Only list objects with at most this many usages.
+        #[clap(long)]
+        max_count: Option<usize>,
+        /// Only list the first n objects.
+        ///
+        /// If the output is piped, the objects are wrapped in a JSON object, that also contains the
+        /// number of matching objects and whether any were left out.
+        #[clap(long)]
+        limit: Option<usize>,
+    },
+
+    /// Combine several data files into one.
+    ///
+    /// The inputs are loaded in their respective formats and merged, the result is written to the
+    /// file passed with `--into` in its format. This doesn't touch the current data file. Like
+    /// changes to the data file, the result isn't written with `--no-save` or `--dry-run`, and
+    /// `--read-only` forbids this command.
+    MergeFiles {
+        /// The data files to combine.
+        #[clap(parse(from_os_str), required = true)]
+        inputs: Vec<PathBuf>,
+        /// The file to write the combined data to.
+        #[clap(long = "into", parse(from_os_str))]
+        output: PathBuf,
+    },
+
+    /// Predict when an object will be used next.
+    ///
+    /// This is a naive estimate: the mean gap between all past usages is added to the last usage.
+    /// It needs at least two usages.
+    NextUse {
+        /// The name of the object.
+        name: String,
+    },
+
+    /// Rewrite the data file in its canonical form, even if nothing changed.
+    ///
+    /// The usages are always kept in chronological order, so the data is only written in the
+    /// canonical formatting of the file format. This keeps diffs clean for users who keep their
+    /// data file in version control.
+    Normalize,
+
+    /// List all objects that were used on a specific day.
+    On {
+        /// The day, in the format 'dd.MM.yyyy'. The day is determined in the local timezone.
+        #[clap(parse(try_from_str = parse_local_date))]
+        date: DateTime<Utc>,
+    },
+
+    /// Compare how often an object was used in the current period to its target rate.
+    ///
+    /// For a target of 5 times per week, the usages within the last 7 days are counted.
+    Progress {
+        /// The name of the object to check.
+        name: String,
     },
 
     /// Remove usages from an object.
+    ///
+    /// With `--all`, usages are removed from all objects and the number of removed usages of every
+    /// affected object is shown.
     Prune {
-        /// Remove all usages before this point in time. If not specified, all usages are removed.
+        /// Remove all usages before this point in time. If neither this nor `--keep-recent` is
+        /// specified, all usages are removed.
         ///
         /// Can be in one of these formats:
         ///
-        /// - 'dd.MM.yyyy': if this format is used, the timezone is set as the local timezone.
+        /// - 'dd.MM.yyyy': if this format is used, the timezone is set as the local timezone, or
+        ///                 UTC with `--utc`. Up to version 0.3.2, UTC was always used.
         /// - 'yyyy-MM-ddThh:mm:ss': if this format is used, the timezone is set as the local
-        ///                          timezone. Intended for use by other programs, but humans should
-        ///                          be able to use it too.
+        ///                          timezone, or UTC with `--utc`. Intended for use by other
+        ///                          programs, but humans should be able to use it too.
         /// - 'yyyy-MM-ddThh:mm:ss+oh:om': this format allows you to specify the timezone yourself.
         ///                                `oh` is the offset hour value, 'om' the offset minute
         ///                                value. Intended for use by other programs.
         #[clap(short, long, parse(try_from_str = parse_date), verbatim_doc_comment)]
-        before: Option<DateTime<Utc>>,
+        before: Option<DateArg>,
+        /// Only keep the usages within this period before now, e.g. `90d`.
+        #[clap(long, parse(try_from_str = parse_duration), conflicts_with = "before")]
+        keep_recent: Option<Duration>,
         /// The name of the object to prune.
+        #[clap(required_unless_present_any = &["pattern", "all"])]
+        name: Option<String>,
+        /// Prune all objects whose names match this regular expression, instead of a single object.
+        #[clap(long, conflicts_with = "name")]
+        pattern: Option<Regex>,
+        /// Prune all objects, instead of a single object.
+        #[clap(long, conflicts_with_all = &["name", "pattern"])]
+        all: bool,
+    },
+
+    /// Show how often an object was used within the last window.
+    ///
+    /// With `--smooth`, the average of the last few windows is shown instead, which jumps less for
+    /// objects that are used irregularly.
+    Rate {
+        /// The name of the object.
         name: String,
+        /// The length of the window, e.g. `7d`.
+        #[clap(long, default_value = "7d", parse(try_from_str = parse_duration))]
+        window: Duration,
+        /// Average over the last few windows.
+        #[clap(long)]
+        smooth: bool,
+    },
+
+    /// List the most recently used objects, most recent first.
+    ///
+    /// Objects that have never been used come last.
+    Recent {
+        /// Only list this many objects.
+        #[clap(long, short)]
+        limit: Option<usize>,
     },
 
+    /// Rewrite the data file in its canonical form, after fixing broken usages.
+    ///
+    /// Like `normalize`, but the usages are sorted chronologically first, in case they aren't
+    /// anymore. Run this after editing the data file manually or importing from a messy source.
+    /// Reports how many objects had to be changed.
+    Rebuild,
+
     /// Remove a currently tracked object permanently.
+    #[clap(visible_alias = "rm")]
     Remove {
         /// The name of the object to remove.
         name: String,
     },
 
+    /// Rename all objects whose names match a regular expression.
+    ///
+    /// The new names are created by replacing the matches with the replacement. If a new name is
+    /// already tracked, the usages of both objects are merged.
+    RenameAll {
+        /// The regular expression to match object names against.
+        pattern: Regex,
+        /// The replacement. Capture groups can be referenced with `$name` or `$1`.
+        replacement: String,
+    },
+
+    /// Serve read-only access to the data via HTTP.
+    ///
+    /// Available endpoints:
+    /// - GET /objects
+    /// - GET /objects/{name}
+    /// - GET /objects/{name}/usage?frame={duration}{unit}, e.g. frame=7d
+    #[cfg(feature = "server")]
+    #[clap(verbatim_doc_comment)]
+    Serve {
+        /// The address to listen on.
+        #[clap(default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+
     /// Show all usages of a single object.
     Show {
         /// The name of the object.
         name: String,
+        /// Only show the first n usages. With `--reverse`, only the last n usages are shown.
+        #[clap(long, short)]
+        limit: Option<usize>,
+        /// Show the newest usages first.
+        #[clap(long, short)]
+        reverse: bool,
+        /// Print where the usages came from, if that is known.
+        #[clap(long, short)]
+        verbose: bool,
+        /// Only show the nth most recent usage, where 0 is the most recent one.
+        ///
+        /// Fails if the object wasn't used often enough.
+        #[clap(long, conflicts_with_all = &["limit", "reverse"])]
+        nth: Option<usize>,
+    },
+
+    /// Show when an object started being tracked and when it was first used.
+    ///
+    /// With `--group`, the combined usages of all members of the group are shown instead. Members
+    /// that aren't tracked anymore are skipped with a warning.
+    Stats {
+        /// The name of the object.
+        #[clap(required_unless_present = "group")]
+        name: Option<String>,
+        /// Show the combined stats of the members of this group.
+        #[clap(long, conflicts_with = "name")]
+        group: Option<String>,
+        /// Also show how many usages were in the morning, afternoon, evening and night.
+        ///
+        /// Segments are determined in the local timezone. The night lasts past midnight until the
+        /// morning starts.
+        #[clap(long, conflicts_with = "group")]
+        segments: bool,
+        /// The hour the morning starts at.
+        #[clap(long, default_value = "6")]
+        morning: u32,
+        /// The hour the afternoon starts at.
+        #[clap(long, default_value = "12")]
+        afternoon: u32,
+        /// The hour the evening starts at.
+        #[clap(long, default_value = "18")]
+        evening: u32,
+        /// The hour the night starts at.
+        #[clap(long, default_value = "22")]
+        night: u32,
+        /// Also take usages in the future into account, e.g. ones recorded with `use --at` as
+        /// reminders.
+        ///
+        /// By default, only usages up to now are taken into account.
+        #[clap(long)]
+        include_future: bool,
+    },
+
+    /// List all objects that haven't been used within a number of days.
+    ///
+    /// Objects that have never been used are always listed.
+    Stale {
+        /// The number of days without usage after which an object is considered stale.
+        days: u32,
+    },
+
+    /// Start a session of an object, e.g. when opening an editor.
+    ///
+    /// The session is recorded as a usage at its start, so `count`, `usage` and the like treat it
+    /// like any other usage. Use `stop-use` to end it. `stats` shows the total time of all
+    /// sessions.
+    StartUse {
+        /// The name of the object.
+        name: String,
+    },
+
+    /// Stop the running session of an object, see `start-use`.
+    StopUse {
+        /// The name of the object.
+        name: String,
+    },
+
+    /// Set how often an object should be used, e.g. 5 times per week.
+    Target {
+        /// The name of the object.
+        name: String,
+        /// How often the object should be used within one unit.
+        #[clap(required_unless_present = "clear")]
+        count: Option<u32>,
+        /// The unit of the period in which the object should be used `count` times.
+        ///
+        /// Allowed values are the same as for the `usage` command.
+        #[clap(required_unless_present = "clear")]
+        unit: Option<DurationUnit>,
+        /// Remove the target rate instead of setting one.
+        #[clap(long, conflicts_with_all = &["count", "unit"])]
+        clear: bool,
+    },
+
+    /// Show the number of usages of all objects combined.
+    Total,
+
+    /// Make sure an object is tracked, optionally with a known start time.
+    ///
+    /// If the object isn't tracked yet, it is added. If it already is, nothing changes, unless
+    /// `--at` is earlier than the time it started being tracked.
+    Touch {
+        /// The name of the object.
+        name: String,
+        /// When the object started being tracked, e.g. for objects used before they were added.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(long, parse(try_from_str = parse_date))]
+        at: Option<DateArg>,
+    },
+
+    /// Remove an alternative name of an object.
+    Unalias {
+        /// The alternative name to remove.
+        alias: String,
+    },
+
+    /// Unarchive an object.
+    Unarchive {
+        /// The name of the object to unarchive.
+        name: String,
+    },
+
+    /// Remove an object from a group, or the whole group if no object is specified.
+    ///
+    /// The objects themselves aren't changed.
+    Ungroup {
+        /// The name of the group.
+        group: String,
+        /// The name of the object to remove from the group.
+        member: Option<String>,
+    },
+
+    /// Remove a single usage of an object, e.g. one recorded by mistake.
+    ///
+    /// The timestamp has to match exactly, as shown by `list --verbose`. Fails if there is no
+    /// usage at that time.
+    Unrecord {
+        /// The name of the object.
+        name: String,
+        /// When the usage was recorded.
+        ///
+        /// Supports the same formats as `prune --before`.
+        #[clap(long, parse(try_from_str = parse_date))]
+        at: DateArg,
+        /// Remove all usages at that time, instead of only one, if several share the timestamp.
+        #[clap(long)]
+        all: bool,
     },
 
     /// Show a prediction of the number of uses of an object within a time frame.
@@ -111,177 +981,2017 @@ enum Commands {
         /// The duration to consider.
         duration: i64,
 
-        ///The type of duration to consider
+        /// The unit of the duration to consider.
         ///
         /// Allowed values:
-        /// - y...year
-        /// - M...month
-        /// - w...week
-        /// - d...day
-        /// - h...hour
-        /// - m...minute
-        /// - s...second
+        /// - y, year
+        /// - M, month
+        /// - w, week
+        /// - d, day
+        /// - h, hour
+        /// - m, minute
+        /// - s, second
+        ///
+        /// Words may also be abbreviated (yr, mo, wk, hr, min, sec), capitalized or plural.
+        ///
+        /// If not specified, the unit in the USAGE_TRACKER_DEFAULT_UNIT environment variable is
+        /// used. If that isn't set either, days are used.
         #[clap(verbatim_doc_comment)]
-        duration_type: char,
+        duration_unit: Option<DurationUnit>,
+        /// The number of decimal places to round the prediction to.
+        ///
+        /// If not specified, the output is rounded to two decimal places for humans, and not
+        /// rounded at all if the output is piped.
+        #[clap(long)]
+        round: Option<usize>,
+        /// Only base the prediction on the usages within this period, e.g. `30d`.
+        ///
+        /// This follows recent changes in how often the object is used. If the period contains
+        /// fewer than two usages, all usages are used instead.
+        #[clap(long, parse(try_from_str = parse_duration))]
+        window: Option<Duration>,
+        /// Take into account whether the object is used more or less often over time.
+        ///
+        /// The rate of usages is assumed to change linearly over time. With fewer than 5 usages,
+        /// the rate is assumed to be constant, like without this flag.
+        #[clap(long, conflicts_with = "window")]
+        trend: bool,
+        /// Also base the prediction on usages in the future, e.g. ones recorded with `use --at` as
+        /// reminders.
+        ///
+        /// By default, only usages up to now are considered.
+        #[clap(long)]
+        include_future: bool,
+        /// What to do if the object has too few usages for a prediction.
+        ///
+        /// Allowed values:
+        /// - error: fail, like for any other error
+        /// - zero: print 0
+        /// - count: print the number of usages, without scaling it to the duration
+        ///
+        /// Objects that were never used always fail.
+        #[clap(
+            long,
+            default_value = "error",
+            parse(try_from_str = parse_on_insufficient),
+            verbatim_doc_comment
+        )]
+        on_insufficient: OnInsufficient,
+    },
+
+    /// Record a new usage of one or more objects.
+    ///
+    /// If multiple objects are specified, the same timestamp is recorded for all of them, and the
+    /// result is shown for each object. Objects that can't be recorded don't stop the others from
+    /// being recorded.
+    #[clap(visible_alias = "u")]
+    Use {
+        /// Add the object if it isn't tracked yet.
+        #[clap(long = "add")]
+        add_if_new: bool,
+        /// The names of the objects that were used.
+        #[clap(required = true)]
+        names: Vec<String>,
+        /// The precision with which the usage is recorded.
+        ///
+        /// Allowed values:
+        /// - seconds
+        /// - millis
+        /// - micros
+        /// - nanos (default)
+        #[clap(long, parse(try_from_str = parse_precision), verbatim_doc_comment)]
+        precision: Option<Precision>,
+        /// Unarchive the object if it is archived.
+        #[clap(long)]
+        unarchive: bool,
+        /// When the usage happened. If not specified, the current time is used.
+        ///
+        /// Supports the same formats as `prune --before`. Dates and times without a timezone are
+        /// interpreted as local time, or as UTC with `--utc`.
+        #[clap(long, parse(try_from_str = parse_date))]
+        at: Option<DateArg>,
+        /// Where the usage came from, e.g. a host or script.
+        #[clap(long)]
+        source: Option<String>,
+        /// Write the usage into the spool directory, instead of loading and saving the data file.
+        ///
+        /// Every call writes its own small file into the spool directory next to the data file
+        /// (e.g. `usages.json.events`), so concurrent calls never wait for each other. This makes
+        /// it suitable for shell hooks. The spooled usages are added to the data file by the next
+        /// `compact` command. Until then, other commands don't see them. Can't be combined with
+        /// `--unarchive` or `--report`.
+        #[clap(long, conflicts_with_all = &["unarchive", "report"])]
+        fast: bool,
+        /// After recording, print the current number of usages per day.
+        #[clap(long)]
+        report: bool,
+        /// Only record the usage if the last usage is at least this long ago, e.g. `30m`.
+        ///
+        /// This counts sessions instead of single usages. Objects without usages always start a new
+        /// session. Can't be combined with `--fast`.
+        #[clap(long, parse(try_from_str = parse_duration), conflicts_with = "fast")]
+        session_gap: Option<Duration>,
     },
 
-    /// Record a new usage of an object.
-    Use {
-        /// Add the object if it isn't tracked yet.
-        #[clap(long = "add")]
-        add_if_new: bool,
-        /// The name of the object that was used.
-        name: String,
-    },
+    /// Repeatedly run another command and show its output.
+    ///
+    /// The data file is reloaded before every run, so changes made by other processes are shown.
+    /// Changes made by the watched command are never saved.
+    ///
+    /// Example: usage-tracker watch --interval 5 -- list --verbose
+    Watch {
+        /// The number of seconds between runs.
+        #[clap(long, short, default_value = "2")]
+        interval: u64,
+        /// The command to run, including its arguments.
+        #[clap(last = true, required = true)]
+        command: Vec<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    // setup panic handler
+    setup_panic!(Metadata {
+        authors: env!("CARGO_PKG_AUTHORS").into(),
+        homepage: env!("CARGO_PKG_HOMEPAGE").into(),
+        name: env!("CARGO_PKG_NAME").into(),
+        version: env!("CARGO_PKG_VERSION").into(),
+    });
+
+    // parse arguments
+    let opt = Opt::parse();
+    let error_format = opt.error_format.unwrap_or(match atty::is(Stream::Stdout) {
+        true => ErrorFormat::Text,
+        false => ErrorFormat::Json,
+    });
+
+    JSON_WARNINGS.store(error_format == ErrorFormat::Json, Ordering::Relaxed);
+    let color = opt.color.enabled(Stream::Stderr);
+
+    if let Err(e) = run(opt) {
+        match error_format {
+            ErrorFormat::Json => eprintln!(
+                "{}",
+                serde_json::json!({ "error": format!("{:#}", e), "kind": error_kind(&e) })
+            ),
+            ErrorFormat::Text => eprintln!("{} {:?}", paint_error("Error:", color), e),
+        }
+
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Executes the command specified by the user.
+fn run(opt: Opt) -> Result<()> {
+    let sp = StandardPaths::new("usage-tracker", "tfld");
+    let data_dir = opt.data_dir.as_deref();
+    let data_file = match (&opt.data_file, data_dir) {
+        (Some(df), _) => Some(df.clone()),
+        (None, Some(_)) => None,
+        (None, None) => project_file()?,
+    };
+    let data_path = match &data_file {
+        Some(df) => df.clone(),
+        None => default_file(&sp, data_dir)?,
+    };
+
+    // output written to a file is only colored if explicitly requested
+    let color = match opt.output {
+        Some(_) => opt.color == ColorChoice::Always,
+        None => opt.color.enabled(Stream::Stdout),
+    };
+
+    if let Some(backup_format) = &opt.backup_format {
+        if formats::by_extension(backup_format).is_none() {
+            return Err(anyhow!(
+                "\"{}\" is not a supported backup format, supported formats are: {}",
+                backup_format,
+                formats::supported_extensions().join(", ")
+            ));
+        }
+    }
+
+    if opt.read_only && opt.cmd.is_mutating() {
+        return Err(anyhow!(
+            "this command changes the data, which `--read-only` forbids"
+        ));
+    }
+
+    // record usages in fast mode without loading data
+    if let Commands::Use {
+        add_if_new,
+        names,
+        precision,
+        at,
+        source,
+        fast: true,
+        ..
+    } = &opt.cmd
+    {
+        if opt.no_save || opt.dry_run {
+            return Ok(());
+        }
+
+        let timestamp = precision.unwrap_or_default().truncate_to(match at {
+            Some(at) => at.resolve(opt.utc)?,
+            None => Utc::now(),
+        });
+        let entries: Vec<_> = names
+            .iter()
+            .map(|name| LogEntry {
+                name: name.to_owned(),
+                timestamp,
+                source: source.to_owned(),
+                add_if_new: opt.auto_add().unwrap_or(*add_if_new),
+            })
+            .collect();
+        write_spool(&entries, &data_path)?;
+
+        return Ok(());
+    }
+
+    // print shell hooks without loading data
+    if let Commands::Hook { shell } = &opt.cmd {
+        print!("{}", hook_snippet(*shell));
+        return Ok(());
+    }
+
+    // rerun another command repeatedly
+    if let Commands::Watch { interval, command } = &opt.cmd {
+        return watch(&opt, *interval, command);
+    }
+
+    // load data
+    let mut info = match &data_file {
+        Some(df) => load_from_file(&df)?,
+        None => load_from_default_files(&sp, data_dir)?,
+    };
+    let mut out = String::new();
+    let mut streamed = false;
+    let mut force_save = false;
+    let mut spool_folded = Vec::new();
+    let mut force_backup = false;
+    let mut appended = Vec::new();
+    let mut all_appendable = false;
+    let mut failed = 0;
+    let auto_add = opt.auto_add();
+    let utc = opt.utc;
+
+    // handle commands
+    match opt.cmd {
+        Commands::Add { name } => info.add(&name)?,
+        Commands::Alias { alias, target } => info.alias(&alias, &target)?,
+        Commands::Archive { name } => info.archive(&name)?,
+        Commands::BulkAdd { file } => {
+            let content = fs::read_to_string(&file).context(format!(
+                "could not read file: {}",
+                file.to_str().context(PATH_CONVERT_ERROR)?
+            ))?;
+            let names: Vec<String> = match content.trim_start().starts_with('[') {
+                true => serde_json::from_str(&content).context(format!(
+                    "could not parse JSON array: {}",
+                    file.to_str().context(PATH_CONVERT_ERROR)?
+                ))?,
+                false => content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+            };
+
+            let mut added = 0;
+            for name in &names {
+                match info.add(name) {
+                    Ok(()) => added += 1,
+                    Err(UsageTrackerError::ObjectAlreadyTracked { .. }) => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            if atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "added {} objects, {} were already tracked",
+                    added,
+                    names.len() - added
+                );
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({ "added": added, "skipped": names.len() - added })
+                );
+            }
+        }
+        Commands::Busiest { name, by } => {
+            let (bucket, count) = info.busiest(&name, by, &Local)?;
+
+            if atty::is(Stream::Stdout) {
+                outln!(out, "{} ({} usages)", bucket, count);
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({ "bucket": bucket.to_string(), "count": count })
+                );
+            }
+        }
+        Commands::Clear { confirmation } => {
+            if !confirmation && (opt.yes || !atty::is(Stream::Stdin)) {
+                return Err(anyhow!("please confirm operation with `--i-am-sure`"));
+            }
+            if !confirmation && !ask("Remove all objects permanently?")? {
+                return Err(anyhow!("aborted"));
+            }
+            info.clear()
+        }
+        Commands::Compact => {
+            let (files, spooled) = read_spool(&data_path)?;
+            for entry in spooled {
+                let LogEntry {
+                    name,
+                    timestamp,
+                    source,
+                    add_if_new,
+                } = entry;
+                if let Err(e) = info.record_use_at(&name, timestamp, source, add_if_new) {
+                    warn(&format!(
+                        "skipping usage of \"{}\" at {}: {}",
+                        name,
+                        timestamp.with_timezone(&Local),
+                        e
+                    ));
+                }
+            }
+            force_save = true;
+            spool_folded = files;
+        }
+        Commands::Copy { from, to } => info.copy(&from, &to)?,
+        Commands::Correlate { a, b, window } => {
+            let count = info.cooccurrence(&a, &b, window)?;
+            let total = info.usages(&a)?.entries().len();
+            let ratio = match total {
+                0 => 0.0,
+                total => count as f64 / total as f64,
+            };
+
+            if atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "{} of {} usages of {} were close to a usage of {} ({}%)",
+                    count,
+                    total,
+                    a,
+                    b,
+                    format_count(ratio * 100.0, 0)
+                );
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({ "count": count, "total": total, "ratio": ratio })
+                );
+            }
+        }
+        Commands::Count {
+            name,
+            pattern,
+            since,
+            include_future,
+        } => {
+            info.set_include_future(include_future);
+            let data = match (name, pattern, since.map(|s| s.resolve(utc)).transpose()?) {
+                (_, Some(pattern), _) => info.count_matching(&pattern)?,
+                (Some(name), None, Some(since)) => info.count_since(&name, &since)?,
+                (Some(name), None, None) => info.count(&name)?,
+                (None, None, _) => unreachable!("clap requires either a name or a pattern"),
+            };
+
+            if atty::is(Stream::Stdout) {
+                outln!(out, "{}", data);
+            } else {
+                outln!(out, "{}", serde_json::json!({ "value": data }));
+            }
+        }
+        Commands::Dedup {
+            case_insensitive,
+            into,
+        } => {
+            let groups: Vec<Vec<String>> = info
+                .duplicates(|name| {
+                    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+                    match case_insensitive {
+                        true => name.to_lowercase(),
+                        false => name,
+                    }
+                })
+                .into_iter()
+                .map(|g| g.into_iter().cloned().collect())
+                .collect();
+
+            if let Some(into) = &into {
+                if !groups.iter().any(|g| g.contains(into)) {
+                    return Err(anyhow!("object \"{}\" has no duplicates", into));
+                }
+            }
+
+            let mut merges = Vec::new();
+            for group in groups {
+                let mut canonical = &group[0];
+                for name in &group {
+                    if info.usages(name)?.entries().len() > info.usages(canonical)?.entries().len()
+                    {
+                        canonical = name;
+                    }
+                }
+                if let Some(into) = into.as_ref().filter(|into| group.contains(into)) {
+                    canonical = into;
+                }
+
+                for name in group.iter().filter(|n| *n != canonical) {
+                    info.merge(name, canonical)?;
+                    merges.push((name.to_owned(), canonical.to_owned()));
+                }
+            }
+
+            if atty::is(Stream::Stdout) {
+                for (from, to) in &merges {
+                    outln!(out, "{} -> {}", from, to);
+                }
+            } else {
+                let output: Vec<_> = merges
+                    .iter()
+                    .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+                    .collect();
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        Commands::Export {
+            names_only,
+            timeline,
+            from,
+            to,
+            anonymize,
+            mapping,
+        } => {
+            let anonymized = match anonymize {
+                true => {
+                    let (anonymized, names) = info.anonymized();
+                    if let Some(mapping) = mapping {
+                        let content =
+                            serde_json::to_string_pretty(&names).context(JSON_FORMAT_ERROR)?;
+                        fs::write(&mapping, content).context(format!(
+                            "failed to write mapping file: {}",
+                            mapping.display()
+                        ))?;
+                    }
+                    Some(anonymized)
+                }
+                false => None,
+            };
+            let info = anonymized.as_ref().unwrap_or(&info);
+
+            if timeline {
+                let from = match from {
+                    Some(from) => Some(from.resolve(utc)?),
+                    None => None,
+                };
+                let to = match to {
+                    Some(to) => Some(to.resolve(utc)?),
+                    None => None,
+                };
+                let events = info
+                    .events_sorted()
+                    .into_iter()
+                    .filter(|(_, at)| !matches!(from, Some(from) if **at < from))
+                    .filter(|(_, at)| !matches!(to, Some(to) if **at > to));
+
+                // timelines can get long, so they are written directly instead of being buffered
+                let writer: Box<dyn Write> = match &opt.output {
+                    Some(path) => Box::new(fs::File::create(path).context(format!(
+                        "could not write output file: {}",
+                        path.to_str().context(PATH_CONVERT_ERROR)?
+                    ))?),
+                    None => Box::new(io::stdout()),
+                };
+                write_timeline(io::BufWriter::new(writer), events)
+                    .context("could not write timeline")?;
+                streamed = true;
+            } else if !names_only {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string_pretty(info).context(JSON_FORMAT_ERROR)?
+                );
+            } else if atty::is(Stream::Stdout) {
+                for k in info.list() {
+                    outln!(out, "{}", k);
+                }
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&info.list()).context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        Commands::Group { group, member } => info.group(&group, &member)?,
+        Commands::Heatmap {
+            name,
+            from,
+            to,
+            csv,
+        } => {
+            let to = to
+                .unwrap_or_else(Utc::now)
+                .with_timezone(&Local)
+                .naive_local()
+                .date();
+            let from = match from {
+                Some(from) => from.with_timezone(&Local).naive_local().date(),
+                None => to - Duration::days(364),
+            };
+            if from > to {
+                return Err(anyhow!("range starts after it ends: {} to {}", from, to));
+            }
+
+            let data = info.usages(&name)?.count_per_day(from, to, &Local);
+            if csv {
+                outln!(out, "# {}: {} to {}", name, from, to);
+                outln!(out, "date,count");
+                for (date, count) in data {
+                    outln!(out, "{},{}", date, count);
+                }
+            } else {
+                let days: Vec<_> = data
+                    .iter()
+                    .map(|(date, count)| serde_json::json!({ "date": date, "count": count }))
+                    .collect();
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({ "name": name, "from": from, "to": to, "days": days })
+                );
+            }
+        }
+        Commands::Import {
+            replace: true,
+            file,
+            confirmation,
+            ..
+        } => {
+            if !confirmation {
+                return Err(anyhow!("please confirm operation with `--i-am-sure`"));
+            }
+
+            // loading a missing file would silently discard all data
+            if url_of(&file).is_none() && !file.is_file() {
+                return Err(anyhow!(
+                    "could not find file: {}",
+                    file.to_str().context(PATH_CONVERT_ERROR)?
+                ));
+            }
+
+            info = load_from_file(&file)?;
+            force_save = true;
+            force_backup = true;
+        }
+        Commands::Import {
+            add_if_new, file, ..
+        } => {
+            let content = fs::read_to_string(&file).context(format!(
+                "could not read file: {}",
+                file.to_str().context(PATH_CONVERT_ERROR)?
+            ))?;
+
+            for (i, line) in content.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let entry: ImportEntry = serde_json::from_str(line)
+                    .context(format!("could not parse line {}", i + 1))?;
+                if entry.count < 1 || entry.count > MAX_IMPORT_COUNT {
+                    return Err(anyhow!(
+                        "count must be between 1 and {} in line {}: {}",
+                        MAX_IMPORT_COUNT,
+                        i + 1,
+                        entry.count
+                    ));
+                }
+
+                for _ in 0..entry.count {
+                    info.record_use_at(
+                        &entry.name,
+                        entry.timestamp,
+                        entry.source.clone(),
+                        add_if_new,
+                    )
+                    .context(format!("could not import line {}", i + 1))?;
+                }
+            }
+        }
+        Commands::Info => {
+            let path = data_path.to_str().context(PATH_CONVERT_ERROR)?;
+            let format = format_of(&data_path)?;
+            let objects = info.list().len();
+            let usages = info.total_usages();
+
+            if atty::is(Stream::Stdout) {
+                outln!(out, "data file:      {}", path);
+                outln!(out, "exists:         {}", data_path.exists());
+                outln!(out, "format:         {}", format.name());
+                outln!(out, "schema version: {}", info.schema_version());
+                outln!(out, "objects:        {}", objects);
+                outln!(out, "usages:         {}", usages);
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({
+                        "data_file": path,
+                        "exists": data_path.exists(),
+                        "format": format.name(),
+                        "schema_version": info.schema_version(),
+                        "objects": objects,
+                        "usages": usages,
+                    })
+                );
+            }
+        }
+        Commands::List {
+            all,
+            percent,
+            verbose,
+            stale,
+            min_count,
+            max_count,
+            limit,
+        } => {
+            let filter = ListFilter {
+                archived: all,
+                min_count,
+                max_count,
+                stale: stale
+                    .map(|days| {
+                        DurationUnit::Day
+                            .checked_duration(days.into())
+                            .ok_or_else(|| anyhow!("number of days is too large: {}", days))
+                    })
+                    .transpose()?,
+            };
+            let objects = info.list_verbose();
+            let mut data: Vec<_> = info
+                .list_filtered(&filter)
+                .into_iter()
+                .map(|k| (k, &objects[k]))
+                .collect();
+
+            // when piped, an empty list is printed as an empty JSON array instead
+            if data.is_empty() && atty::is(Stream::Stdout) {
+                if stale.is_some() || min_count.is_some() || max_count.is_some() {
+                    return Err(anyhow!("no objects match the filters"));
+                }
+                return Err(anyhow!("no objects are currently tracked"));
+            }
+
+            // shares are relative to all matching objects, not only the listed ones
+            let total: usize = data.iter().map(|(_, v)| v.entries().len()).sum();
+            let matching = data.len();
+            if let Some(limit) = limit {
+                data.truncate(limit);
+            }
+            let omitted = matching - data.len();
+            let limited = |output: serde_json::Value| match limit {
+                Some(_) => serde_json::json!({
+                    "objects": output,
+                    "total": matching,
+                    "truncated": omitted > 0,
+                }),
+                None => output,
+            };
+
+            if percent {
+                let share = |count: usize| match total {
+                    0 => 0.0,
+                    _ => count as f64 / total as f64 * 100.0,
+                };
+
+                if atty::is(Stream::Stdout) {
+                    let name_width = data.iter().map(|(k, _)| k.chars().count()).max();
+                    let name_width = name_width.unwrap_or(0);
+                    let count_width = total.to_string().len();
+
+                    for (i, (k, v)) in data.iter().enumerate() {
+                        let count = v.entries().len();
+                        outln!(
+                            out,
+                            "{}: {}  {}  {:>6.2}%",
+                            i,
+                            paint_name(&format!("{:<w$}", k, w = name_width), color),
+                            paint_count(&format!("{:>w$}", count, w = count_width), color),
+                            share(count),
+                        );
+                    }
+                } else {
+                    let output: Vec<_> = data
+                        .iter()
+                        .map(|(k, v)| {
+                            let count = v.entries().len();
+                            serde_json::json!({
+                                "name": k,
+                                "count": count,
+                                "percent": share(count)
+                            })
+                        })
+                        .collect();
+                    outln!(out, "{}", limited(serde_json::json!(output)));
+                }
+            } else if !verbose {
+                let data: Vec<_> = data.into_iter().map(|(k, _)| k).collect();
+
+                if atty::is(Stream::Stdout) {
+                    for (i, k) in data.iter().enumerate() {
+                        outln!(out, "{}: {}", i, paint_name(k, color));
+                    }
+                } else {
+                    outln!(out, "{}", limited(serde_json::json!(data)));
+                }
+            } else {
+                if atty::is(Stream::Stdout) {
+                    for (i, (k, v)) in data.iter().enumerate() {
+                        outln!(
+                            out,
+                            "{}: {} (tracked since {}){}",
+                            i,
+                            paint_name(k, color),
+                            v.created_at().with_timezone(&Local),
+                            if v.archived() { " [archived]" } else { "" }
+                        );
+                        for u in v.entries() {
+                            outln!(out, "   {}", u.at().with_timezone(&Local));
+                        }
+                    }
+                } else {
+                    let mut output = Vec::new();
+                    for (k, v) in data.iter() {
+                        output.push(serde_json::json!({
+                            "name": k,
+                            "created_at": v.created_at(),
+                            "archived": v.archived(),
+                            "usages": v.list()
+                        }));
+                    }
+                    outln!(out, "{}", limited(serde_json::json!(output)));
+                }
+            }
+
+            if omitted > 0 && atty::is(Stream::Stdout) {
+                outln!(out, "... and {} more", omitted);
+            }
+        }
+        Commands::MergeFiles { inputs, output } => {
+            // fail before loading anything if the output format is unknown
+            format_of(&output)?;
+
+            let mut merged = UsageInformation::new();
+            let mut contributions = Vec::new();
+            for input in &inputs {
+                let path = input.to_str().context(PATH_CONVERT_ERROR)?;
+                if url_of(input).is_none() && !input.is_file() {
+                    return Err(anyhow!("could not find file: {}", path));
+                }
+
+                let data = load_from_file(input)?;
+                contributions.push((path, data.list().len(), data.total_usages()));
+                for name in merged.merge_from(data) {
+                    warn(&format!(
+                        "skipping object \"{}\" of {}, its name is already used by an \
+                         alias",
+                        name, path
+                    ));
+                }
+            }
+
+            if !opt.no_save && !opt.dry_run {
+                save_to_file(
+                    &merged,
+                    &output,
+                    !opt.no_backup,
+                    opt.backup_format.as_deref(),
+                    opt.epoch_seconds,
+                )?;
+            }
+
+            if atty::is(Stream::Stdout) {
+                for (path, objects, usages) in &contributions {
+                    outln!(out, "{}: {} objects, {} usages", path, objects, usages);
+                }
+                outln!(
+                    out,
+                    "merged into {}: {} objects, {} usages",
+                    output.to_str().context(PATH_CONVERT_ERROR)?,
+                    merged.list().len(),
+                    merged.total_usages()
+                );
+            } else {
+                let files: Vec<_> = contributions
+                    .iter()
+                    .map(|(path, objects, usages)| {
+                        serde_json::json!({
+                            "file": path,
+                            "objects": objects,
+                            "usages": usages,
+                        })
+                    })
+                    .collect();
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({
+                        "inputs": files,
+                        "output": output.to_str().context(PATH_CONVERT_ERROR)?,
+                        "objects": merged.list().len(),
+                        "usages": merged.total_usages(),
+                    })
+                );
+            }
+        }
+        Commands::NextUse { name } => {
+            let now = Utc::now();
+            let data = info.predict_next_use(&name, now)?;
+
+            if atty::is(Stream::Stdout) {
+                match data {
+                    Some(next) if next >= now => outln!(
+                        out,
+                        "{} (in {})",
+                        next.with_timezone(&Local),
+                        format_duration(next - now)
+                    ),
+                    Some(next) => outln!(
+                        out,
+                        "{} (overdue by {})",
+                        next.with_timezone(&Local),
+                        format_duration(now - next)
+                    ),
+                    None => outln!(out, "not enough usages to predict the next one"),
+                }
+            } else {
+                outln!(out, "{}", serde_json::json!({ "next_use": data }));
+            }
+        }
+        Commands::Normalize => force_save = true,
+        Commands::On { date } => {
+            let data =
+                info.objects_used_on(date.with_timezone(&Local).naive_local().date(), &Local);
+
+            if atty::is(Stream::Stdout) {
+                for k in data {
+                    outln!(out, "{}", k);
+                }
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&data).context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        Commands::Progress { name } => {
+            let (actual, target) = info.progress(&name)?;
+            let percent = match target {
+                0 => 100.0,
+                target => actual as f64 / target as f64 * 100.0,
+            };
+
+            if atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "{} of {} ({}%)",
+                    actual,
+                    target,
+                    format_count(percent, 0)
+                );
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({ "actual": actual, "target": target, "percent": percent })
+                );
+            }
+        }
+        Commands::Prune {
+            before,
+            keep_recent,
+            name,
+            pattern,
+            all,
+        } => {
+            let before = match (before, keep_recent) {
+                (Some(before), _) => Some(before.resolve(utc)?),
+                (None, Some(keep_recent)) => Some(time_before(Utc::now(), keep_recent)),
+                (None, None) => None,
+            };
+            let names: Vec<String> = match (name, &pattern) {
+                (Some(name), _) => vec![name],
+                (None, Some(pattern)) => info.search(pattern).into_iter().cloned().collect(),
+                (None, None) if all => info.list().into_iter().cloned().collect(),
+                (None, None) => unreachable!("clap requires either a name, a pattern or --all"),
+            };
+
+            if names.is_empty() && all {
+                outln!(out, "no objects are currently tracked");
+            } else if names.is_empty() {
+                outln!(out, "no objects match the pattern");
+            } else if opt.dry_run {
+                for name in &names {
+                    let count = info.prune_preview(name, &before)?;
+                    let affected: Vec<_> = info
+                        .usages(name)?
+                        .entries()
+                        .iter()
+                        .map(Usage::at)
+                        .filter(|u| !matches!(before, Some(b) if **u >= b))
+                        .collect();
+
+                    match (affected.iter().min(), affected.iter().max()) {
+                        (Some(first), Some(last)) => outln!(
+                            out,
+                            "{}: would remove {} usages from {} to {}",
+                            name,
+                            count,
+                            first.with_timezone(&Local),
+                            last.with_timezone(&Local)
+                        ),
+                        _ => outln!(out, "{}: would remove no usages", name),
+                    }
+                }
+            } else if all {
+                confirm("Remove usages from all objects?", opt.yes)?;
+                let report = info.prune_all(&before);
+
+                if atty::is(Stream::Stdout) {
+                    for (name, removed) in &report {
+                        outln!(out, "{}: removed {} usages", name, removed);
+                    }
+                    outln!(
+                        out,
+                        "removed {} usages from {} objects",
+                        report.values().sum::<usize>(),
+                        report.len()
+                    );
+                } else {
+                    outln!(
+                        out,
+                        "{}",
+                        serde_json::to_string(&report).context(JSON_FORMAT_ERROR)?
+                    );
+                }
+            } else {
+                let mut removed = 0;
+                for name in &names {
+                    removed += info.prune(name, &before)?;
+                }
+
+                if pattern.is_some() {
+                    outln!(
+                        out,
+                        "removed {} usages from {} objects",
+                        removed,
+                        names.len()
+                    );
+                }
+            }
+        }
+        Commands::Rate {
+            name,
+            window,
+            smooth,
+        } => {
+            let now = Utc::now();
+            let data = match smooth {
+                true => info.smoothed_rate(&name, window, now)?,
+                false => info.count_since(&name, &time_before(now, window))? as f64,
+            };
+
+            if atty::is(Stream::Stdout) {
+                outln!(out, "{}", format_count(data, 2));
+            } else {
+                outln!(out, "{}", serde_json::json!({ "value": data }));
+            }
+        }
+        Commands::Recent { limit } => {
+            let data = info.recently_used(limit);
+
+            if atty::is(Stream::Stdout) {
+                for (k, last) in data {
+                    match last {
+                        Some(last) => outln!(
+                            out,
+                            "{} (last used {} ago)",
+                            k,
+                            format_duration(Utc::now() - *last)
+                        ),
+                        None => outln!(out, "{} (never used)", k),
+                    }
+                }
+            } else {
+                let output: Vec<_> = data
+                    .iter()
+                    .map(|(k, last)| serde_json::json!({ "name": k, "last_used": last }))
+                    .collect();
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        Commands::Rebuild => {
+            let modified = info.rebuild();
+            force_save = true;
+
+            if atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "modified {} objects",
+                    paint_count(&modified.to_string(), color)
+                );
+            } else {
+                outln!(out, "{}", serde_json::json!({ "modified": modified }));
+            }
+        }
+        Commands::Remove { name } => {
+            confirm(
+                &format!("Remove \"{}\" permanently?", name),
+                opt.yes || opt.dry_run,
+            )?;
+            info.remove(&name)
+        }
+        Commands::RenameAll {
+            pattern,
+            replacement,
+        } => {
+            let names: Vec<String> = info.search(&pattern).into_iter().cloned().collect();
+
+            let mut mapping = Vec::new();
+            for name in names {
+                let new_name = pattern
+                    .replace_all(&name, replacement.as_str())
+                    .into_owned();
+                if new_name == name {
+                    continue;
+                }
+
+                if info.usages(&new_name).is_ok() {
+                    info.merge(&name, &new_name)?;
+                } else {
+                    info.rename(&name, &new_name)?;
+                }
+                mapping.push((name, new_name));
+            }
+
+            if atty::is(Stream::Stdout) {
+                for (from, to) in &mapping {
+                    outln!(out, "{} -> {}", from, to);
+                }
+            } else {
+                let output: Vec<_> = mapping
+                    .iter()
+                    .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+                    .collect();
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        #[cfg(feature = "server")]
+        Commands::Serve { addr } => server::serve(&info, &addr)?,
+        Commands::Show {
+            name,
+            limit,
+            reverse,
+            verbose,
+            nth,
+        } => {
+            let usages = info.usages(&name)?;
+            let mut data: Vec<_> = usages.entries().iter().collect();
+            if reverse {
+                data.reverse();
+            }
+            if let Some(limit) = limit {
+                data.truncate(limit);
+            }
+            if let Some(n) = nth {
+                data = match info.nth_recent(&name, n)? {
+                    Some(u) => vec![u],
+                    None => {
+                        return Err(anyhow!(
+                            "object \"{}\" has only been used {} times",
+                            name,
+                            usages.entries().len()
+                        ))
+                    }
+                };
+            }
+
+            if atty::is(Stream::Stdout) {
+                for u in data {
+                    match u.source() {
+                        Some(source) if verbose => {
+                            outln!(out, "{} ({})", u.at().with_timezone(&Local), source)
+                        }
+                        _ => outln!(out, "{}", u.at().with_timezone(&Local)),
+                    }
+                }
+            } else if verbose {
+                let output: Vec<_> = data
+                    .iter()
+                    .map(|u| serde_json::json!({ "timestamp": u.at(), "source": u.source() }))
+                    .collect();
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
+                );
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&data.iter().map(|u| u.at()).collect::<Vec<_>>())
+                        .context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        Commands::Stale { days } => {
+            let older_than = DurationUnit::Day
+                .checked_duration(days.into())
+                .ok_or_else(|| anyhow!("number of days is too large: {}", days))?;
+            let data = info.stale(older_than);
+
+            if atty::is(Stream::Stdout) {
+                for k in data {
+                    match info.usages(k)?.last() {
+                        Some(last) => outln!(
+                            out,
+                            "{} (last used {} ago)",
+                            k,
+                            format_duration(Utc::now() - *last)
+                        ),
+                        None => outln!(out, "{} (never used)", k),
+                    }
+                }
+            } else {
+                let mut output = Vec::new();
+                for k in data {
+                    output.push(serde_json::json!({
+                        "name": k,
+                        "never_used": info.usages(k)?.entries().is_empty()
+                    }));
+                }
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
+                );
+            }
+        }
+        Commands::Target {
+            name,
+            count,
+            unit,
+            clear: _,
+        } => info.set_target(&name, count.zip(unit))?,
+        Commands::StartUse { name } => {
+            info.start_use(&name, auto_add.unwrap_or(false))?;
+        }
+        Commands::StopUse { name } => {
+            let duration = info.stop_use(&name)?;
+
+            if atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "session of {} lasted {}",
+                    paint_name(&name, color),
+                    paint_count(&format_duration(duration), color)
+                );
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({ "seconds": duration.num_seconds() })
+                );
+            }
+        }
+        Commands::Stats {
+            name: None,
+            group: Some(group),
+            include_future,
+            ..
+        } => {
+            info.set_include_future(include_future);
+            let members =
+                info.groups()
+                    .get(&group)
+                    .ok_or_else(|| UsageTrackerError::GroupNotDefined {
+                        group: group.to_owned(),
+                    })?;
+
+            let mut tracked = Vec::new();
+            for member in members {
+                match info.usages(member) {
+                    Ok(usages) => tracked.push((member, usages, info.counted_usages(member)?)),
+                    Err(_) => warn(&format!(
+                        "object \"{}\" of group \"{}\" isn't tracked, skipping it",
+                        member, group
+                    )),
+                }
+            }
+
+            let count: usize = tracked.iter().map(|(_, _, c)| c.len()).sum();
+            let created_at = tracked.iter().map(|(_, u, _)| u.created_at()).min();
+            let first = tracked
+                .iter()
+                .filter_map(|(_, _, c)| c.first().map(Usage::at))
+                .min();
+            let last = tracked
+                .iter()
+                .filter_map(|(_, _, c)| c.last().map(Usage::at))
+                .max();
+
+            if atty::is(Stream::Stdout) {
+                outln!(out, "members:       {}", tracked.len());
+                outln!(
+                    out,
+                    "usages:        {}",
+                    paint_count(&count.to_string(), color)
+                );
+                if let Some(created_at) = created_at {
+                    outln!(out, "tracked since: {}", created_at.with_timezone(&Local));
+                }
+                match (first, last) {
+                    (Some(first), Some(last)) => {
+                        outln!(out, "first used:    {}", first.with_timezone(&Local));
+                        outln!(out, "last used:     {}", last.with_timezone(&Local));
+                    }
+                    _ => outln!(out, "first used:    {}", paint_count("never", color)),
+                }
+            } else {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::json!({
+                        "members": tracked.iter().map(|(m, _, _)| m).collect::<Vec<_>>(),
+                        "usages": count,
+                        "created_at": created_at,
+                        "first_used": first,
+                        "last_used": last,
+                    })
+                );
+            }
+        }
+        Commands::Stats {
+            name,
+            segments,
+            morning,
+            afternoon,
+            evening,
+            night,
+            include_future,
+            ..
+        } => {
+            info.set_include_future(include_future);
+            let name = name.expect("clap requires either a name or a group");
+            let usages = info.usages(&name)?;
+            let first = info.counted_usages(&name)?.first().map(Usage::at);
+            // the first counted usage is the first usage at all, unless every usage is left out
+            let idle = first.and(usages.idle_before_first_use());
+            let total_time = info.total_time(&name)?;
+
+            let starts = [morning, afternoon, evening, night];
+            if segments && (starts.windows(2).any(|w| w[0] >= w[1]) || night >= 24) {
+                return Err(anyhow!(
+                    "segments must start in the order morning, afternoon, evening, night, at hours \
+                     from 0 to 23"
+                ));
+            }
+            let segment_names = ["morning", "afternoon", "evening", "night"];
+            let counts = match segments {
+                true => Some(info.count_per_segment(&name, &starts, &Local)?),
+                false => None,
+            };
+
+            if atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "tracked since: {}",
+                    usages.created_at().with_timezone(&Local)
+                );
+                match (first, idle) {
+                    (Some(first), Some(idle)) => {
+                        outln!(out, "first used:    {}", first.with_timezone(&Local));
+                        outln!(
+                            out,
+                            "idle before:   {}",
+                            paint_count(&format_duration(idle), color)
+                        );
+                    }
+                    _ => outln!(out, "first used:    {}", paint_count("never", color)),
+                }
+                if total_time > Duration::zero() {
+                    outln!(
+                        out,
+                        "time used:     {}",
+                        paint_count(&format_duration(total_time), color)
+                    );
+                }
+                if let Some(counts) = &counts {
+                    for (segment, count) in segment_names.iter().zip(counts) {
+                        outln!(
+                            out,
+                            "{:<15}{}",
+                            format!("{}:", segment),
+                            paint_count(&count.to_string(), color)
+                        );
+                    }
+                }
+            } else {
+                let mut output = serde_json::json!({
+                    "created_at": usages.created_at(),
+                    "first_used": first,
+                    "days_idle_before_first_use": idle.map(|i| i.num_days()),
+                    "seconds_used": total_time.num_seconds(),
+                });
+                if let Some(counts) = &counts {
+                    let segments: serde_json::Map<_, _> = segment_names
+                        .iter()
+                        .zip(counts)
+                        .map(|(segment, count)| (segment.to_string(), serde_json::json!(count)))
+                        .collect();
+                    output["segments"] = serde_json::Value::Object(segments);
+                }
+                outln!(out, "{}", output);
+            }
+        }
+        Commands::Total => {
+            let data = info.total_usages();
+            if atty::is(Stream::Stdout) {
+                outln!(out, "{}", data);
+            } else {
+                outln!(out, "{}", serde_json::json!({ "value": data }));
+            }
+        }
+        Commands::Touch { name, at } => {
+            let at = match at {
+                Some(at) => Some(at.resolve(utc)?),
+                None => None,
+            };
+            info.touch(&name, at)?;
+        }
+        Commands::Unalias { alias } => info.unalias(&alias)?,
+        Commands::Unarchive { name } => info.unarchive(&name)?,
+        Commands::Ungroup { group, member } => info.ungroup(&group, member.as_ref())?,
+        Commands::Unrecord { name, at, all } => {
+            let at = at.resolve(utc)?;
+            if !info.remove_usage_at(&name, at, all)? {
+                return Err(anyhow!(
+                    "object \"{}\" has no usage at {}",
+                    name,
+                    at.with_timezone(&Local)
+                ));
+            }
+        }
+        Commands::Usage {
+            name,
+            duration,
+            duration_unit,
+            round,
+            window,
+            trend,
+            include_future,
+            on_insufficient,
+        } => {
+            info.set_include_future(include_future);
+            let duration_unit = match duration_unit {
+                Some(unit) => unit,
+                None => default_duration_unit()?,
+            };
+            let time_frame = duration_unit
+                .checked_duration(duration)
+                .ok_or_else(|| anyhow!("duration is too long: {} {:?}", duration, duration_unit))?;
+            let data = match window {
+                Some(window) => info.usage_over(&name, &time_frame, &window),
+                None if trend => info.usage_trend(&name, &time_frame),
+                None => info.usage(&name, &time_frame),
+            };
+            let data = match (data, on_insufficient) {
+                (Err(UsageTrackerError::InsufficientData { .. }), OnInsufficient::Count) => {
+                    info.count(&name)? as f64
+                }
+                (Err(UsageTrackerError::InsufficientData { .. }), OnInsufficient::Zero) => 0.0,
+                (data, _) => data?,
+            };
+            if atty::is(Stream::Stdout) {
+                outln!(out, "{}", format_count(data, round.unwrap_or(2)));
+            } else {
+                let data = match round {
+                    Some(round) => {
+                        let factor = 10f64.powi(round as i32);
+                        (data * factor).round() / factor
+                    }
+                    None => data,
+                };
+                outln!(out, "{}", serde_json::json!({ "value": data }));
+            }
+        }
+        Commands::Use {
+            add_if_new,
+            names,
+            precision,
+            at,
+            unarchive,
+            source,
+            report,
+            fast: _,
+            session_gap,
+        } => {
+            all_appendable = true;
+            let add_if_new = auto_add.unwrap_or(add_if_new);
+            let timestamp = precision.unwrap_or_default().truncate_to(match at {
+                Some(at) => at.resolve(utc)?,
+                None => Utc::now(),
+            });
+
+            let mut entries = Vec::new();
+            let mut output = Vec::new();
+            for name in &names {
+                let outcome = match record_use(
+                    &mut info,
+                    name,
+                    timestamp,
+                    &source,
+                    add_if_new,
+                    unarchive,
+                    session_gap,
+                ) {
+                    Ok(outcome) => outcome,
+                    Err(e) if names.len() == 1 => return Err(e.into()),
+                    Err(e) => {
+                        failed += 1;
+                        if atty::is(Stream::Stdout) {
+                            outln!(out, "{}: error: {}", name, e);
+                        } else {
+                            output.push(serde_json::json!({
+                                "name": name,
+                                "result": "error",
+                                "error": e.to_string()
+                            }));
+                        }
+                        continue;
+                    }
+                };
+
+                // only the usage itself changed, so it can be appended to JSON Lines files
+                if outcome == UseOutcome::Recorded {
+                    entries.push(LogEntry {
+                        name: name.clone(),
+                        timestamp,
+                        source: source.clone(),
+                        add_if_new,
+                    });
+                } else if outcome != UseOutcome::Skipped {
+                    all_appendable = false;
+                }
+
+                let prediction = match report {
+                    true => match info.usage(name, &Duration::days(1)) {
+                        Ok(data) => Some(data),
+                        Err(UsageTrackerError::InsufficientData { .. }) => None,
+                        Err(e) => return Err(e.into()),
+                    },
+                    false => None,
+                };
+
+                if names.len() == 1 {
+                    if report {
+                        if atty::is(Stream::Stdout) {
+                            match prediction {
+                                Some(data) => outln!(out, "{}", format_count(data, 2)),
+                                None => outln!(out, "not enough usages for a prediction yet"),
+                            }
+                        } else {
+                            outln!(out, "{}", serde_json::json!({ "value": prediction }));
+                        }
+                    }
+                } else if atty::is(Stream::Stdout) {
+                    match (report, prediction) {
+                        (true, Some(data)) => outln!(
+                            out,
+                            "{}: {} ({} per day)",
+                            name,
+                            outcome,
+                            format_count(data, 2)
+                        ),
+                        _ => outln!(out, "{}: {}", name, outcome),
+                    }
+                } else if report {
+                    output.push(serde_json::json!({
+                        "name": name,
+                        "result": outcome.to_string(),
+                        "value": prediction
+                    }));
+                } else {
+                    output.push(serde_json::json!({
+                        "name": name,
+                        "result": outcome.to_string()
+                    }));
+                }
+            }
+
+            if names.len() > 1 && !atty::is(Stream::Stdout) {
+                outln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
+                );
+            }
+            appended = entries;
+        }
+        Commands::Hook { .. } => unreachable!("hook is handled before loading data"),
+        Commands::Watch { .. } => unreachable!("watch is handled before loading data"),
+    }
+
+    // print or write output, unless the command already did so itself
+    match &opt.output {
+        _ if streamed => {}
+        Some(path) => fs::write(path, &out).context(format!(
+            "could not write output file: {}",
+            path.to_str().context(PATH_CONVERT_ERROR)?
+        ))?,
+        None => print!("{}", out),
+    }
+
+    // if data changed, safe new data
+    if !opt.no_save && !opt.dry_run && !opt.read_only && (force_save || info.is_dirty()) {
+        if all_appendable && !force_save && format_of(&data_path)?.appendable() {
+            for entry in &appended {
+                append_entry(entry, &data_path)?;
+            }
+        } else {
+            save_to_file(
+                &info,
+                &data_path,
+                force_backup || !opt.no_backup,
+                opt.backup_format.as_deref(),
+                opt.epoch_seconds,
+            )?;
+        }
+        info.mark_clean();
+
+        // only delete the spool files that were read, others may have been written meanwhile
+        for file in &spool_folded {
+            fs::remove_file(file).context("couldn't clear spool file")?;
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("could not record the usages of {} objects", failed));
+    }
+
+    Ok(())
+}
+
+/// Records a usage of `name` at `timestamp` for the `use` command.
+///
+/// If `session_gap` is set and the last usage is less than that before `timestamp`, nothing is
+/// recorded. An archived object is still unarchived if `unarchive` is set.
+fn record_use(
+    info: &mut UsageInformation,
+    name: &String,
+    timestamp: DateTime<Utc>,
+    source: &Option<String>,
+    add_if_new: bool,
+    unarchive: bool,
+    session_gap: Option<Duration>,
+) -> std::result::Result<UseOutcome, UsageTrackerError> {
+    let archived = info.usages(name).ok().map(Usages::archived);
+
+    if unarchive && archived == Some(true) {
+        info.unarchive(name)?;
+    }
+
+    match session_gap {
+        Some(gap) => {
+            // within the current session, the usage isn't recorded
+            if !info.record_use_at_if_idle(name, timestamp, source.clone(), gap, add_if_new)? {
+                return Ok(UseOutcome::Skipped);
+            }
+        }
+        None => info.record_use_at(name, timestamp, source.clone(), add_if_new)?,
+    }
+
+    Ok(match archived {
+        None => UseOutcome::Created,
+        Some(false) => UseOutcome::Recorded,
+        Some(true) => UseOutcome::Unarchived,
+    })
+}
+
+/// Repeatedly runs `command` every `interval` seconds, clearing the screen before every run.
+///
+/// The global options of `opt` are passed on to the command, but changes are never saved. Errors
+/// are shown instead of the output and don't stop the loop. This way, a data file that is briefly
+/// missing or incomplete while another process saves it doesn't end watching.
+fn watch(opt: &Opt, interval: u64, command: &[String]) -> Result<()> {
+    /// Parses the watched command.
+    #[derive(Debug, Parser)]
+    struct WatchedCommand {
+        #[clap(subcommand)]
+        cmd: Commands,
+    }
+
+    loop {
+        let cmd = WatchedCommand::try_parse_from(
+            std::iter::once("watch".to_owned()).chain(command.iter().cloned()),
+        )?
+        .cmd;
+        if let Commands::Watch { .. } = cmd {
+            return Err(anyhow!("watch can't watch itself"));
+        }
+
+        let watched = Opt {
+            cmd,
+            data_file: opt.data_file.clone(),
+            data_dir: opt.data_dir.clone(),
+            no_backup: opt.no_backup,
+            backup_format: opt.backup_format.clone(),
+            no_save: true,
+            dry_run: opt.dry_run,
+            output: None,
+            error_format: Some(ErrorFormat::Text),
+            color: opt.color,
+            auto_add: opt.auto_add,
+            no_auto_add: opt.no_auto_add,
+            read_only: opt.read_only,
+            yes: opt.yes,
+            utc: opt.utc,
+            epoch_seconds: opt.epoch_seconds,
+        };
+
+        // clear screen and move cursor to the top left corner
+        print!("\x1B[2J\x1B[H");
+        if let Err(e) = run(watched) {
+            eprintln!(
+                "{} {:?}",
+                paint_error("Error:", opt.color.enabled(Stream::Stderr)),
+                e
+            );
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(interval));
+    }
+}
+
+/// Asks the user a yes or no question on the terminal. Only an answer starting with `y` counts as
+/// yes.
+fn ask(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush().context("could not print question")?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .context("could not read answer")?;
+
+    Ok(answer.trim().to_lowercase().starts_with('y'))
+}
+
+/// Asks the user to confirm removing data, and fails if they don't.
+///
+/// The user is only asked on a terminal and if `skip` isn't set, scripts always proceed.
+fn confirm(question: &str, skip: bool) -> Result<()> {
+    if skip || !atty::is(Stream::Stdin) {
+        return Ok(());
+    }
+
+    match ask(question)? {
+        true => Ok(()),
+        false => Err(anyhow!("aborted")),
+    }
+}
+
+/// Appends a usage as a single line of JSON to the file at `path`. The file is created if it
+/// doesn't exist yet.
+fn append_entry(entry: &LogEntry, path: &PathBuf) -> Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!(
+            "could not open file for appending: {}",
+            path.to_str().context(PATH_CONVERT_ERROR)?
+        ))?;
+
+    let mut line = serde_json::to_string(entry).context(JSON_FORMAT_ERROR)?;
+    line.push('\n');
+    file.write_all(line.as_bytes()).context(format!(
+        "could not append to file: {}",
+        path.to_str().context(PATH_CONVERT_ERROR)?
+    ))
+}
+
+/// Provides the directory the default data files are stored in.
+///
+/// The directory is resolved by `standard_paths`, which honors the platform conventions:
+/// - Linux: `$XDG_DATA_HOME/tfld/usage-tracker`, falling back to
+///   `~/.local/share/tfld/usage-tracker` if `XDG_DATA_HOME` isn't set.
+/// - Windows: `%APPDATA%\tfld\usage-tracker` (the roaming application data folder).
+///
+/// If the user passed a directory with `--data-dir`, that one is used instead.
+fn data_directory(sp: &StandardPaths, data_dir: Option<&Path>) -> Result<PathBuf> {
+    match data_dir {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => sp
+            .writable_location(LocationType::AppDataLocation)
+            .context("application data directory not found"),
+    }
+}
+
+/// Provides a stable name for the kind of an error, for machine readable error output.
+fn error_kind(e: &anyhow::Error) -> &'static str {
+    match e.downcast_ref::<UsageTrackerError>() {
+        Some(UsageTrackerError::AliasNotDefined { .. }) => "AliasNotDefined",
+        Some(UsageTrackerError::AliasShadowsObject { .. }) => "AliasShadowsObject",
+        Some(UsageTrackerError::FileLoadErrorRon(_)) => "FileLoadErrorRon",
+        Some(UsageTrackerError::GroupNotDefined { .. }) => "GroupNotDefined",
+        Some(UsageTrackerError::InsufficientData { .. }) => "InsufficientData",
+        Some(UsageTrackerError::InvalidDurationUnit { .. }) => "InvalidDurationUnit",
+        Some(UsageTrackerError::InvalidName { .. }) => "InvalidName",
+        Some(UsageTrackerError::InvalidPattern { .. }) => "InvalidPattern",
+        Some(UsageTrackerError::NoTarget { .. }) => "NoTarget",
+        Some(UsageTrackerError::ObjectAlreadyTracked { .. }) => "ObjectAlreadyTracked",
+        Some(UsageTrackerError::ObjectArchived { .. }) => "ObjectArchived",
+        Some(UsageTrackerError::ObjectNeverUsed { .. }) => "ObjectNeverUsed",
+        Some(UsageTrackerError::ObjectNotTracked { .. }) => "ObjectNotTracked",
+        Some(UsageTrackerError::SessionAlreadyRunning { .. }) => "SessionAlreadyRunning",
+        Some(UsageTrackerError::SessionNotRunning { .. }) => "SessionNotRunning",
+        None => "Other",
+    }
+}
+
+/// Formats a count for human readers.
+///
+/// The value is rounded to `decimals` decimal places and thousands separators are added.
+fn format_count(value: f64, decimals: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (int, frac) = match formatted.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut grouped = String::new();
+    for (i, c) in int.chars().enumerate() {
+        if i != 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+
+    format!(
+        "{}{}{}",
+        if value < 0.0 { "-" } else { "" },
+        grouped,
+        frac.map(|f| format!(".{}", f)).unwrap_or_default()
+    )
 }
 
-fn main() -> Result<()> {
-    // setup panic handler
-    setup_panic!(Metadata {
-        authors: env!("CARGO_PKG_AUTHORS").into(),
-        homepage: env!("CARGO_PKG_HOMEPAGE").into(),
-        name: env!("CARGO_PKG_NAME").into(),
-        version: env!("CARGO_PKG_VERSION").into(),
-    });
+/// Formats a duration for human readers, e.g. `7d 3h`.
+///
+/// Only the two most significant non-zero units are shown.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().abs();
+    let units = [
+        (secs / 86400, "d"),
+        (secs % 86400 / 3600, "h"),
+        (secs % 3600 / 60, "m"),
+        (secs % 60, "s"),
+    ];
 
-    // parse arguments
-    let opt = Opt::parse();
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(v, _)| *v == 0)
+        .take(2)
+        .filter(|(v, _)| *v != 0)
+        .map(|(v, u)| format!("{}{}", v, u))
+        .collect();
 
-    // load data
-    let sp = StandardPaths::new("usage-tracker", "tfld");
-    let initial_info = match &opt.data_file {
-        Some(df) => load_from_file(&df)?,
-        None => load_from_default_files(&sp)?,
-    };
-    let mut info = initial_info.clone();
+    if parts.is_empty() {
+        "0s".to_owned()
+    } else {
+        parts.join(" ")
+    }
+}
 
-    // handle commands
-    match opt.cmd {
-        Commands::Add { name } => info.add(&name)?,
-        Commands::Clear { confirmation } => {
-            if confirmation {
-                info.clear()
-            } else {
-                return Err(anyhow!("please confirm operation with `--i-am-sure`"));
-            }
-        }
-        Commands::List { verbose } => {
-            if info.list_verbose().len() == 0 {
-                return Err(anyhow!("no objects are currently tracked"));
-            }
+/// Highlights an object name in human readable output, if `color` is set.
+fn paint_name(name: &str, color: bool) -> String {
+    match color {
+        true => name.cyan().bold().to_string(),
+        false => name.to_owned(),
+    }
+}
 
-            if !verbose {
-                let data = info.list();
+/// Highlights a count or other value in human readable output, if `color` is set.
+fn paint_count(count: &str, color: bool) -> String {
+    match color {
+        true => count.yellow().to_string(),
+        false => count.to_owned(),
+    }
+}
 
-                if atty::is(Stream::Stdout) {
-                    for (i, k) in data.iter().enumerate() {
-                        println!("{}: {}", i, k);
-                    }
-                } else {
-                    println!(
-                        "{}",
-                        serde_json::to_string(&data).context(JSON_FORMAT_ERROR)?
-                    );
-                }
-            } else {
-                let data = info.list_verbose();
+/// Highlights the prefix of an error message, if `color` is set.
+fn paint_error(prefix: &str, color: bool) -> String {
+    match color {
+        true => prefix.red().bold().to_string(),
+        false => prefix.to_owned(),
+    }
+}
 
-                if atty::is(Stream::Stdout) {
-                    for (i, (k, v)) in data.iter().enumerate() {
-                        println!("{}: {}", i, k);
-                        for u in v.list() {
-                            println!("   {}", u.with_timezone(&Local));
-                        }
-                    }
-                } else {
-                    let mut output = Vec::new();
-                    for (k, v) in data.iter() {
-                        output.push(serde_json::json!({"name": k, "usages": v.list()}));
-                    }
-                    println!(
-                        "{}",
-                        serde_json::to_string(&output).context(JSON_FORMAT_ERROR)?
-                    );
-                }
-            }
+/// Prints a warning to stderr, in the same format as errors.
+fn warn(message: &str) {
+    match JSON_WARNINGS.load(Ordering::Relaxed) {
+        true => eprintln!("{}", serde_json::json!({ "warning": message })),
+        false => eprintln!("Warning: {}", message),
+    }
+}
+
+/// Provides a snippet for `shell`, that records the first word of every command with
+/// `use --fast --add`.
+fn hook_snippet(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => {
+            r#"__usage_tracker_last=$(HISTTIMEFORMAT= history 1)
+__usage_tracker_hook() {
+    local entry cmd
+    entry=$(HISTTIMEFORMAT= history 1)
+    if [ "$entry" != "$__usage_tracker_last" ]; then
+        __usage_tracker_last=$entry
+        cmd=$(printf '%s\n' "$entry" | sed -e 's/^ *[0-9]* *//' | awk '{ print $1 }')
+        if [ -n "$cmd" ]; then
+            usage-tracker use --fast --add "$cmd" >/dev/null 2>&1
+        fi
+    fi
+}
+PROMPT_COMMAND="__usage_tracker_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+"#
         }
-        Commands::Prune { before, name } => info.prune(&name, &before)?,
-        Commands::Remove { name } => info.remove(&name),
-        Commands::Show { name } => {
-            let data = (info.usages(&name)?).list();
-            if atty::is(Stream::Stdout) {
-                for u in data {
-                    println!("{}", u.with_timezone(&Local));
-                }
-            } else {
-                println!(
-                    "{}",
-                    serde_json::to_string(&data).context(JSON_FORMAT_ERROR)?
-                );
-            }
+        Shell::Zsh => {
+            r#"__usage_tracker_hook() {
+    local cmd=${${(z)1}[1]}
+    if [[ -n $cmd ]]; then
+        usage-tracker use --fast --add "$cmd" >/dev/null 2>&1
+    fi
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec __usage_tracker_hook
+"#
         }
-        Commands::Usage {
-            name,
-            duration,
-            duration_type,
-        } => {
-            let d = match duration_type {
-                'y' => Duration::days(duration * 365),
-                'M' => Duration::days(duration * 30),
-                'w' => Duration::weeks(duration),
-                'd' => Duration::days(duration),
-                'h' => Duration::hours(duration),
-                'm' => Duration::minutes(duration),
-                's' => Duration::seconds(duration),
-                _ => {
-                    return Err(anyhow!("duration type '{}' doesn't exist", duration_type));
-                }
-            };
+    }
+}
 
-            let data = info.usage(&name, &d)?;
-            if atty::is(Stream::Stdout) {
-                println!("{}", data);
-            } else {
-                println!("{}", serde_json::json!({ "value": data }));
-            }
-        }
-        Commands::Use { add_if_new, name } => info.record_use(&name, add_if_new)?,
+/// Writes usages into a new file in the spool directory belonging to the data file at `path`.
+///
+/// The file is written under a temporary name and renamed afterwards, so `read_spool()` never sees
+/// a partially written file. The name is unique for every call, so concurrent calls don't
+/// interfere.
+fn write_spool(entries: &[LogEntry], path: &PathBuf) -> Result<()> {
+    if url_of(path).is_some() {
+        return Err(anyhow!("writing data to a URL is not supported"));
     }
 
-    // if data changed, safe new data
-    if info != initial_info {
-        match &opt.data_file {
-            Some(df) => save_to_file(&info, &df, !opt.no_backup)?,
-            None => save_to_default_file(&info, !opt.no_backup, &sp)?,
-        }
+    let spool = spool_path(path);
+    fs::create_dir_all(&spool).context(format!(
+        "could not create spool directory: {}",
+        spool.to_str().context(PATH_CONVERT_ERROR)?
+    ))?;
+
+    // the timestamp first keeps the files in the order they were written in
+    let name = format!(
+        "{:020}-{}",
+        Utc::now().timestamp_nanos(),
+        std::process::id()
+    );
+    let tmp = spool.join(format!("{}.tmp", name));
+    let file = spool.join(format!("{}.jsonl", name));
+
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry).context(JSON_FORMAT_ERROR)?);
+        content.push('\n');
     }
+    fs::write(&tmp, content).context(format!(
+        "could not write spool file: {}",
+        tmp.to_str().context(PATH_CONVERT_ERROR)?
+    ))?;
+    fs::rename(&tmp, &file).context(format!(
+        "could not write spool file: {}",
+        file.to_str().context(PATH_CONVERT_ERROR)?
+    ))
+}
 
-    Ok(())
+/// Provides the path of the spool directory belonging to the data file at `path`. This is the data
+/// files path with `.events` appended.
+fn spool_path(path: &PathBuf) -> PathBuf {
+    let mut spool = path.clone().into_os_string();
+    spool.push(".events");
+    spool.into()
+}
+
+/// Provides the data to start with if no default file exists.
+///
+/// If built with the `embedded-default` feature, this is the JSON data file whose path was in the
+/// `USAGE_TRACKER_DEFAULT_DATA` environment variable at build time. This allows shipping a curated
+/// set of objects. Relative paths are resolved from the `src` directory.
+#[cfg(embedded_default_data)]
+fn initial_data() -> Result<UsageInformation> {
+    serde_json::from_str(include_str!(env!("USAGE_TRACKER_DEFAULT_DATA")))
+        .context("could not parse embedded default data")
+}
+
+/// Provides the data to start with if no default file exists.
+///
+/// The program was built without the `embedded-default` feature, or without the
+/// `USAGE_TRACKER_DEFAULT_DATA` environment variable, so this is empty.
+#[cfg(not(embedded_default_data))]
+fn initial_data() -> Result<UsageInformation> {
+    Ok(UsageInformation::new())
+}
+
+/// Checks whether the file at `path` is empty. If it is, a warning is printed to stderr.
+///
+/// An empty data file most likely is the result of an interrupted save, so it is treated like a
+/// missing file instead of failing to parse it.
+fn is_empty_file(path: &PathBuf) -> Result<bool> {
+    let empty = fs::metadata(path)
+        .context(format!(
+            "could not read file metadata: {}",
+            path.to_str().context(PATH_CONVERT_ERROR)?
+        ))?
+        .len()
+        == 0;
+
+    if empty {
+        warn(&format!(
+            "data file is empty, treating it as if it didn't exist: {}",
+            path.to_str().context(PATH_CONVERT_ERROR)?
+        ));
+    }
+
+    Ok(empty)
 }
 
-/// Loads usage information from one of two default files.
+/// Loads usage information from one of the default files.
 ///
 /// The files are always tried in the same order, an later files are only tried when the former file
 /// wasn't found, but not if any other error occurred. All files are within the OS-specific
-/// application data directory:
-/// 1. `usages.json`: this is also the file the program writes to by default.
+/// application data directory, or the directory passed with `--data-dir`:
+/// 1. `usages.<ext>`: for every extension in `formats::supported_extensions()`, in that order.
+///    The file is loaded like a file passed by the user. `usages.json` is also the file the
+///    program writes to by default.
 /// 2. `default.ron`: this was the default file in 0.1, so 0.2 should be able to fall back to it.
-fn load_from_default_files(sp: &StandardPaths) -> Result<UsageInformation> {
+///
+/// If none of the files exist, the data provided by `initial_data()` is used.
+fn load_from_default_files(
+    sp: &StandardPaths,
+    data_dir: Option<&Path>,
+) -> Result<UsageInformation> {
     // get application data directory
-    let path_base = sp
-        .writable_location(LocationType::AppDataLocation)
-        .context("application data directory not found")?;
+    let path_base = data_directory(sp, data_dir)?;
 
-    let files = vec![("usages", true), ("default", false)];
+    let files = formats::supported_extensions()
+        .into_iter()
+        .map(|ext| ("usages", ext, false))
+        .chain(std::iter::once(("default", "ron", true)));
 
-    for (name, is_json) in files {
+    for (name, ext, is_legacy) in files {
         let mut p = PathBuf::new();
         p.push(&path_base);
         p.push(name);
-        p.set_extension(match is_json {
-            true => "json",
-            false => "ron",
-        });
+        p.set_extension(ext);
 
         if !p.exists() {
             continue;
@@ -294,109 +3004,374 @@ fn load_from_default_files(sp: &StandardPaths) -> Result<UsageInformation> {
             ));
         }
 
+        if !is_legacy {
+            return load_from_file(&p);
+        }
+
+        if is_empty_file(&p)? {
+            return Ok(UsageInformation::new());
+        }
+
         let file = File::open(Path::new(&p)).context(format!(
             "could not open file: {}",
             p.to_str().context(PATH_CONVERT_ERROR)?
         ))?;
 
-        return match is_json {
-            true => serde_json::from_reader(file).context(format!(
-                "could not parse JSON file: {}",
-                p.to_str().context(PATH_CONVERT_ERROR)?
-            )),
-            #[allow(deprecated)]
-            false => UsageInformation::load_usage_information_from_ron_file(file).context(format!(
-                "could not load data from RON file: {}",
-                p.to_str().context(PATH_CONVERT_ERROR)?
-            )),
-        };
+        #[allow(deprecated)]
+        return UsageInformation::load_usage_information_from_ron_file(file).context(format!(
+            "could not load data from RON file: {}",
+            p.to_str().context(PATH_CONVERT_ERROR)?
+        ));
     }
 
-    Ok(UsageInformation::new())
+    initial_data()
 }
 
-/// Loads usage information from a file.
+/// Decides the file format on basis of the file extension.
 ///
-/// The file format is decided on basis of the file extension. Currently supported formats:
+/// Currently supported formats:
 /// - JSON: `.json`
+/// - JSON Lines: `.jsonl`, see `JsonLinesEntry`
+/// - CBOR: `.cbor`, only if built with the `cbor` feature
+///
+/// Formats are registered in `formats::FORMATS`.
+fn format_of(path: &PathBuf) -> Result<&'static dyn formats::DataFormat> {
+    match path.extension() {
+        Some(e) => {
+            let e = e.to_str().context("could not parse file name extension")?;
+            formats::by_extension(e).ok_or_else(|| {
+                anyhow!(
+                    "\"{}\" is not a supported file format, supported formats are: {}",
+                    e,
+                    formats::supported_extensions().join(", ")
+                )
+            })
+        }
+        None => Err(anyhow!("file format not specified")),
+    }
+}
+
+/// Loads usage information from a file.
+///
+/// The file format is decided by `format_of()`.
 fn load_from_file(path: &PathBuf) -> Result<UsageInformation> {
-    let fmt = match path.extension() {
-        Some(e) => match e.to_str().context("could not parse file name extension")? {
-            "json" => "JSON",
-            _ => {
-                return Err(anyhow!(
-                    "\"{}\" is not a supported file format",
-                    e.to_str().context(PATH_CONVERT_ERROR)?
-                ))
-            }
-        },
-        None => return Err(anyhow!("file format not specified")),
-    };
+    if let Some(url) = url_of(path) {
+        return load_from_url(url);
+    }
+
+    let fmt = format_of(path)?;
 
     if !path.exists() {
         return Ok(UsageInformation::new());
     }
 
+    if is_empty_file(path)? {
+        return Ok(UsageInformation::new());
+    }
+
     let file = File::open(Path::new(&path)).context(format!(
         "could not open file: {}",
         path.to_str().context(PATH_CONVERT_ERROR)?
     ))?;
 
-    match fmt {
-        "JSON" => serde_json::from_reader(file),
-        _ => panic!("internal format value changed"),
-    }
-    .context(format!(
+    fmt.load(file).context(format!(
         "could not parse {} file: {}",
-        fmt,
+        fmt.name(),
         path.to_str().context(PATH_CONVERT_ERROR)?
     ))
 }
 
-/// Parses a &str into a DateTime<Utc>.
+/// Loads usage information from a JSON file provided via http or https.
+#[cfg(feature = "remote")]
+fn load_from_url(url: &str) -> Result<UsageInformation> {
+    let response = ureq::get(url)
+        .call()
+        .context(format!("could not fetch URL: {}", url))?;
+
+    serde_json::from_reader(response.into_reader())
+        .context(format!("could not parse JSON file: {}", url))
+}
+
+/// Fails, because the program was built without support for loading data from URLs.
+#[cfg(not(feature = "remote"))]
+fn load_from_url(url: &str) -> Result<UsageInformation> {
+    Err(anyhow!(
+        "loading data from a URL requires the `remote` feature: {}",
+        url
+    ))
+}
+
+/// Reads all usages from the spool directory belonging to the data file at `path`.
+///
+/// Returns the files that were read, so they can be deleted once the usages are saved, and the
+/// usages in the order they were written. Files that are still being written are skipped. Lines
+/// that can't be parsed are skipped with a warning, and are deleted together with their file.
+fn read_spool(path: &PathBuf) -> Result<(Vec<PathBuf>, Vec<LogEntry>)> {
+    let spool = spool_path(path);
+    if !spool.is_dir() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&spool).context(format!(
+        "could not read spool directory: {}",
+        spool.to_str().context(PATH_CONVERT_ERROR)?
+    ))? {
+        let file = entry
+            .context("could not read spool directory entry")?
+            .path();
+        if matches!(file.extension(), Some(e) if e == "jsonl") {
+            files.push(file);
+        }
+    }
+    files.sort();
+
+    let mut entries = Vec::new();
+    for file in &files {
+        let file = file.to_str().context(PATH_CONVERT_ERROR)?;
+        let content =
+            fs::read_to_string(file).context(format!("could not read spool file: {}", file))?;
+
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn(&format!(
+                    "skipping line {} of spool file {}: {}",
+                    i + 1,
+                    file,
+                    e
+                )),
+            }
+        }
+    }
+
+    Ok((files, entries))
+}
+
+/// Parses a &str into a DateTime<Utc>, like `parse_date()`. Dates and times without a timezone are
+/// always interpreted as local time.
+fn parse_local_date(src: &str) -> Result<DateTime<Utc>> {
+    parse_date(src)?.resolve(false)
+}
+
+/// Converts a local date and time into UTC.
+///
+/// If the local time is ambiguous (e.g. when clocks are turned back), the earlier point in time is
+/// used.
+fn local_to_utc(dt: &NaiveDateTime) -> Result<DateTime<Utc>> {
+    Ok(Local
+        .from_local_datetime(dt)
+        .earliest()
+        .ok_or(anyhow!("local time doesn't exist: {}", dt))?
+        .into())
+}
+
+/// Writes `events` to `writer` as a JSON array, one event at a time.
+fn write_timeline<'a>(
+    mut writer: impl Write,
+    events: impl Iterator<Item = (&'a String, &'a DateTime<Utc>)>,
+) -> Result<()> {
+    writer.write_all(b"[")?;
+    for (i, (name, at)) in events.enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut writer, &serde_json::json!({ "at": at, "name": name }))?;
+    }
+    writer.write_all(b"]\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Provides the point in time `duration` before `now`.
+///
+/// Durations reaching before the earliest point in time chrono can represent end there instead, as
+/// nothing can have happened before anyway.
+fn time_before(now: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    now.checked_sub_signed(duration)
+        .unwrap_or(chrono::MIN_DATETIME)
+}
+
+/// Parses a &str into a DateArg.
 ///
-/// Tries different formats described by the documentation for the `prune -v` command parameter.
-fn parse_date(src: &str) -> Result<DateTime<Utc>> {
+/// Tries different formats described by the documentation for the `prune --before` command
+/// parameter. Dates and times without a timezone are kept without one, until `DateArg::resolve()`
+/// decides how to interpret them.
+fn parse_date(src: &str) -> Result<DateArg> {
     if src.len() == "dd.MM.yyyy".len() {
         let d = NaiveDate::parse_from_str(src, "%d.%m.%Y")
             .context(format!("could not parse local date: {}", src))?;
-        return Ok(Utc
-            .from_local_datetime(
-                &d.and_hms_opt(0, 0, 0)
-                    .ok_or(anyhow!("could not convert to utc: {}", d))?,
-            )
-            .unwrap());
+        let dt = d
+            .and_hms_opt(0, 0, 0)
+            .ok_or(anyhow!("could not convert to utc: {}", d))?;
+
+        return Ok(DateArg::Naive(dt));
     } else if src.len() == "yyyy-MM-ddThh:mm:ss".len() {
         let dt: NaiveDateTime = src
             .parse()
             .context(format!("could not pares local datetime: {}", src))?;
 
-        let dtu = Local.from_local_datetime(&dt).unwrap();
-
-        return Ok(dtu.into());
+        return Ok(DateArg::Naive(dt));
     } else {
-        return Ok(src
-            .parse()
-            .context(format!("could not parse datetime: {}", src))?);
+        return Ok(DateArg::Zoned(
+            src.parse()
+                .context(format!("could not parse datetime: {}", src))?,
+        ));
     }
 }
 
-/// Saves the provided UsageInformation to a default file. The default file is the first file listed
-/// in the documentation of `load_from_default_files()`.
+/// Parses a &str like `30d` into a Duration.
 ///
-/// The parameter `backup` specifies whether or not the function will create a backup of the
-/// original file (if one exists), before overwriting it. This backup is very simple, it's literally
-/// adding `.bak` to the original files name. If a file with that name already exists, it is
-/// deleted.
-fn save_to_default_file(ui: &UsageInformation, backup: bool, sp: &StandardPaths) -> Result<()> {
-    // get file path
-    let mut path = sp
-        .writable_location(LocationType::AppDataLocation)
-        .context("application data directory not found")?;
+/// The units are the same as for the `usage` command.
+fn parse_duration(src: &str) -> Result<Duration> {
+    let split = src
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or(anyhow!("duration has no unit: {}", src))?;
+    let (amount, unit) = src.split_at(split);
+
+    let amount: i64 = amount
+        .parse()
+        .context(format!("invalid duration: {}", src))?;
+    let unit: DurationUnit = unit.parse()?;
+
+    if amount < 1 {
+        return Err(anyhow!("duration must be positive: {}", src));
+    }
+
+    unit.checked_duration(amount)
+        .ok_or_else(|| anyhow!("duration is too long: {}", src))
+}
+
+/// Parses a &str into a ColorChoice.
+fn parse_color_choice(src: &str) -> Result<ColorChoice> {
+    match src {
+        "always" => Ok(ColorChoice::Always),
+        "auto" => Ok(ColorChoice::Auto),
+        "never" => Ok(ColorChoice::Never),
+        _ => Err(anyhow!("color choice '{}' doesn't exist", src)),
+    }
+}
+
+/// Parses a &str into an ErrorFormat.
+fn parse_error_format(src: &str) -> Result<ErrorFormat> {
+    match src {
+        "json" => Ok(ErrorFormat::Json),
+        "text" => Ok(ErrorFormat::Text),
+        _ => Err(anyhow!("error format '{}' doesn't exist", src)),
+    }
+}
+
+/// Parses a &str into an OnInsufficient.
+fn parse_on_insufficient(src: &str) -> Result<OnInsufficient> {
+    match src {
+        "count" => Ok(OnInsufficient::Count),
+        "error" => Ok(OnInsufficient::Error),
+        "zero" => Ok(OnInsufficient::Zero),
+        _ => Err(anyhow!("fallback '{}' doesn't exist", src)),
+    }
+}
+
+/// Parses a &str into a Shell.
+fn parse_shell(src: &str) -> Result<Shell> {
+    match src {
+        "bash" => Ok(Shell::Bash),
+        "zsh" => Ok(Shell::Zsh),
+        _ => Err(anyhow!("shell '{}' isn't supported", src)),
+    }
+}
+
+/// Parses a &str into a Granularity.
+fn parse_granularity(src: &str) -> Result<Granularity> {
+    match src {
+        "hour" => Ok(Granularity::Hour),
+        "weekday" => Ok(Granularity::Weekday),
+        "month" => Ok(Granularity::Month),
+        _ => Err(anyhow!("granularity '{}' doesn't exist", src)),
+    }
+}
+
+/// Parses a &str into a Precision.
+fn parse_precision(src: &str) -> Result<Precision> {
+    match src {
+        "seconds" => Ok(Precision::Seconds),
+        "millis" => Ok(Precision::Millis),
+        "micros" => Ok(Precision::Micros),
+        "nanos" => Ok(Precision::Nanos),
+        _ => Err(anyhow!("precision '{}' doesn't exist", src)),
+    }
+}
+
+/// Provides the path as a URL, if it is a http or https URL.
+fn url_of(path: &PathBuf) -> Option<&str> {
+    path.to_str()
+        .filter(|p| p.starts_with("http://") || p.starts_with("https://"))
+}
+
+/// Provides the unit for the `usage` command, if the user didn't specify one.
+///
+/// This is the unit in the `USAGE_TRACKER_DEFAULT_UNIT` environment variable, or days if that
+/// isn't set.
+fn default_duration_unit() -> Result<DurationUnit> {
+    match std::env::var(DEFAULT_UNIT_VARIABLE) {
+        Ok(unit) => unit
+            .parse()
+            .context(format!("invalid {}", DEFAULT_UNIT_VARIABLE)),
+        Err(std::env::VarError::NotPresent) => Ok(DurationUnit::Day),
+        Err(e) => Err(e).context(format!("invalid {}", DEFAULT_UNIT_VARIABLE)),
+    }
+}
+
+/// Provides the path of the default file. The default file is the first file listed in the
+/// documentation of `load_from_default_files()`.
+fn default_file(sp: &StandardPaths, data_dir: Option<&Path>) -> Result<PathBuf> {
+    let mut path = data_directory(sp, data_dir)?;
     path.push("usages");
     path.set_extension("json");
 
-    save_to_file(ui, &path, backup)
+    Ok(path)
+}
+
+/// Finds the data file of the project the current directory belongs to.
+///
+/// The current directory and all its parents up to the filesystem root are searched, the closest
+/// directory containing one of these files wins:
+/// 1. `.usage-tracker`: contains the path of the data file. Relative paths are resolved from the
+///    directory containing the file.
+/// 2. `.usage-tracker.json`: is the data file itself.
+///
+/// If neither file exists, `None` is returned.
+fn project_file() -> Result<Option<PathBuf>> {
+    let cwd = std::env::current_dir().context("could not determine current directory")?;
+
+    for dir in cwd.ancestors() {
+        let pointer = dir.join(PROJECT_POINTER_FILE);
+        if pointer.is_file() {
+            let content = fs::read_to_string(&pointer).context(format!(
+                "could not read file: {}",
+                pointer.to_str().context(PATH_CONVERT_ERROR)?
+            ))?;
+            let target = content.trim();
+            if target.is_empty() {
+                return Err(anyhow!(
+                    "project file doesn't contain a data file path: {}",
+                    pointer.to_str().context(PATH_CONVERT_ERROR)?
+                ));
+            }
+
+            return Ok(Some(dir.join(target)));
+        }
+
+        let data = dir.join(PROJECT_DATA_FILE);
+        if data.is_file() {
+            return Ok(Some(data));
+        }
+    }
+
+    Ok(None)
 }
 
 /// Saves the provided UsageInformation to a default file. The default file is the first file listed
@@ -406,21 +3381,38 @@ fn save_to_default_file(ui: &UsageInformation, backup: bool, sp: &StandardPaths)
 /// original file (if one exists), before overwriting it. This backup is very simple, it's literally
 /// adding `.bak` to the original files name. If a file with that name already exists, it is
 /// deleted.
-fn save_to_file(ui: &UsageInformation, path: &PathBuf, backup: bool) -> Result<()> {
-    let fmt = match path.extension() {
-        Some(e) => match e.to_str().context("could not parse file name extension")? {
-            "json" => "JSON",
-            _ => {
-                return Err(anyhow!(
-                    "\"{}\" is not a supported file format",
-                    e.to_str().context(PATH_CONVERT_ERROR)?
-                ))
-            }
-        },
-        None => return Err(anyhow!("file format not specified")),
-    };
+///
+/// If `backup_format` is set, the backup is instead written in that format, by loading the
+/// original file and saving it to a file with `.bak.<format>` appended to the original files name.
+///
+/// The parameter `epoch_seconds` specifies whether usages are stored as seconds since the Unix
+/// epoch instead of RFC 3339 strings.
+fn save_to_file(
+    ui: &UsageInformation,
+    path: &PathBuf,
+    backup: bool,
+    backup_format: Option<&str>,
+    epoch_seconds: bool,
+) -> Result<()> {
+    if url_of(path).is_some() {
+        return Err(anyhow!("writing data to a URL is not supported"));
+    }
+
+    let fmt = format_of(path)?;
 
-    if backup {
+    if let (true, Some(backup_format)) = (backup, backup_format) {
+        let mut backup_path = path.clone().into_os_string();
+        backup_path.push(".bak.");
+        backup_path.push(backup_format);
+        let backup_path = PathBuf::from(backup_path);
+
+        // convert the old file, the backup is replaced as a whole
+        if path.exists() {
+            let old = load_from_file(path)?;
+            save_to_file(&old, &backup_path, false, None, epoch_seconds)
+                .context("couldn't write backup file")?;
+        }
+    } else if backup {
         // get backup path
         let mut backup_path = PathBuf::new();
         backup_path.push(&path);
@@ -455,13 +3447,95 @@ fn save_to_file(ui: &UsageInformation, path: &PathBuf, backup: bool) -> Result<(
         path.to_str().context(PATH_CONVERT_ERROR)?
     ))?;
 
-    match fmt {
-        "JSON" => serde_json::to_writer_pretty(file, ui),
-        _ => panic!("internal format value changed"),
-    }
-    .context(format!(
-        "could not parse {} file: {}",
-        fmt,
+    fmt.save(file, ui, epoch_seconds).context(format!(
+        "could not write {} file: {}",
+        fmt.name(),
         path.to_str().context(PATH_CONVERT_ERROR)?
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn data_directory_follows_xdg_data_home() {
+        let sp = StandardPaths::new("usage-tracker", "tfld");
+        let xdg = std::env::var_os("XDG_DATA_HOME");
+        let home = std::env::var_os("HOME");
+
+        std::env::set_var("XDG_DATA_HOME", "/tmp/xdg-data");
+        assert_eq!(
+            data_directory(&sp, None).unwrap(),
+            PathBuf::from("/tmp/xdg-data/tfld/usage-tracker")
+        );
+
+        std::env::remove_var("XDG_DATA_HOME");
+        std::env::set_var("HOME", "/tmp/home");
+        assert_eq!(
+            data_directory(&sp, None).unwrap(),
+            PathBuf::from("/tmp/home/.local/share/tfld/usage-tracker")
+        );
+
+        // an explicit directory wins over the environment
+        assert_eq!(
+            default_file(&sp, Some(Path::new("/srv/usages"))).unwrap(),
+            PathBuf::from("/srv/usages/usages.json")
+        );
+
+        match xdg {
+            Some(xdg) => std::env::set_var("XDG_DATA_HOME", xdg),
+            None => std::env::remove_var("XDG_DATA_HOME"),
+        }
+        match home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[test]
+    fn empty_data_files_are_treated_as_missing() {
+        for ext in formats::supported_extensions() {
+            let path = std::env::temp_dir().join(format!(
+                "usage-tracker-{}-empty.{}",
+                std::process::id(),
+                ext
+            ));
+            File::create(&path).unwrap();
+            let loaded = load_from_file(&path);
+            fs::remove_file(&path).unwrap();
+
+            let info = loaded.unwrap();
+            assert!(info.list().is_empty(), "{} file wasn't empty", ext);
+            assert!(!info.is_dirty());
+        }
+    }
+
+    #[test]
+    fn time_before_stops_at_the_earliest_representable_time() {
+        let now = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            time_before(now, Duration::days(1)),
+            Utc.ymd(2020, 12, 31).and_hms(12, 0, 0)
+        );
+
+        let keep_recent = parse_duration("1000000y").unwrap();
+        assert_eq!(time_before(now, keep_recent), chrono::MIN_DATETIME);
+
+        // pruning before the earliest representable time keeps every usage
+        let mut info = UsageInformation::new();
+        info.record_use_at("milk", now, None, true).unwrap();
+        let before = time_before(now, keep_recent);
+        info.prune(&"milk".to_owned(), &Some(before)).unwrap();
+        assert_eq!(info.count(&"milk".to_owned()).unwrap(), 1);
+    }
+
+    #[test]
+    fn parse_duration_rejects_out_of_range_durations() {
+        assert_eq!(parse_duration("2w").unwrap(), Duration::days(14));
+        assert!(parse_duration("999999999999y").is_err());
+        assert!(parse_duration("99999999999999999999d").is_err());
+        assert!(parse_duration("0d").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// A callback that is invoked whenever a usage is recorded.
+///
+/// Callbacks must be `Send`, so data with registered callbacks can still be moved to another
+/// thread.
+pub type RecordCallback = Box<dyn FnMut(&str, DateTime<Utc>) + Send>;
+
+/// Keeps the callbacks registered on a `UsageInformation`.
+///
+/// Callbacks are attached to a specific instance, not to the data it contains. Therefore they are
+/// neither cloned nor compared.
+#[derive(Default)]
+pub(crate) struct Observers {
+    on_record: Vec<RecordCallback>,
+}
+
+impl Observers {
+    /// Registers a new callback for recorded usages.
+    pub(crate) fn add_on_record(&mut self, cb: RecordCallback) {
+        self.on_record.push(cb);
+    }
+
+    /// Invokes all callbacks for recorded usages.
+    pub(crate) fn notify_record(&mut self, name: &str, timestamp: DateTime<Utc>) {
+        for cb in &mut self.on_record {
+            cb(name, timestamp);
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("on_record", &self.on_record.len())
+            .finish()
+    }
+}
+
+impl PartialEq for Observers {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Observers {}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, SubsecRound, Utc};
+
+/// The precision with which new usages are recorded.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Precision {
+    /// Whole seconds.
+    Seconds,
+    /// Milliseconds.
+    Millis,
+    /// Microseconds.
+    Micros,
+    /// Nanoseconds. This is the full precision of the system clock.
+    #[default]
+    Nanos,
+}
+
+impl Precision {
+    /// Truncates the provided timestamp to this precision.
+    pub fn truncate_to(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        timestamp.trunc_subsecs(match self {
+            Precision::Seconds => 0,
+            Precision::Millis => 3,
+            Precision::Micros => 6,
+            Precision::Nanos => 9,
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use tiny_http::{Header, Method, Request, Response, Server};
+use usage_tracker::*;
+
+/// Serves read-only access to the provided usage information via HTTP.
+///
+/// Available endpoints:
+/// - `GET /objects`: all currently tracked objects.
+/// - `GET /objects/{name}`: all usages of a single object.
+/// - `GET /objects/{name}/usage?frame={duration}{unit}`: a prediction of the number of uses of an
+///   object within a time frame, e.g. `frame=7d`. The units are the same as for the `usage`
+///   command.
+pub fn serve(info: &UsageInformation, addr: &str) -> Result<()> {
+    let server = Server::http(addr).map_err(|e| anyhow!("could not start server: {}", e))?;
+
+    for request in server.incoming_requests() {
+        let (status, body) = match handle(info, &request) {
+            Ok(body) => (200, body),
+            Err((status, msg)) => (status, serde_json::json!({ "error": msg })),
+        };
+
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                    .expect("static header is valid"),
+            );
+
+        // a failed response only affects a single client, so keep serving the others
+        let _ = request.respond(response);
+    }
+
+    Ok(())
+}
+
+/// Handles a single request.
+///
+/// On failure, the HTTP status code and an error message are returned.
+fn handle(info: &UsageInformation, request: &Request) -> Result<serde_json::Value, (u16, String)> {
+    if request.method() != &Method::Get {
+        return Err((405, "only GET requests are supported".to_owned()));
+    }
+
+    let (path, query) = match request.url().split_once('?') {
+        Some((p, q)) => (p, Some(q)),
+        None => (request.url(), None),
+    };
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        ["objects"] => Ok(serde_json::json!(info.list())),
+        ["objects", name] => {
+            let name = percent_decode(name)?;
+            let usages = info.usages(&name).map_err(|e| (404, e.to_string()))?;
+            Ok(serde_json::json!(usages.list()))
+        }
+        ["objects", name, "usage"] => {
+            let name = percent_decode(name)?;
+            let frame = query
+                .and_then(|q| q.split('&').find_map(|p| p.strip_prefix("frame=")))
+                .ok_or((400, "missing query parameter: frame".to_owned()))?;
+            let duration = crate::parse_duration(frame).map_err(|e| (400, e.to_string()))?;
+
+            let value = info.usage(&name, &duration).map_err(|e| match e {
+                UsageTrackerError::ObjectNotTracked { .. } => (404, e.to_string()),
+                _ => (422, e.to_string()),
+            })?;
+            Ok(serde_json::json!({ "value": value }))
+        }
+        _ => Err((404, format!("unknown endpoint: {}", path))),
+    }
+}
+
+/// Decodes a percent-encoded URL path segment.
+fn percent_decode(src: &str) -> Result<String, (u16, String)> {
+    let invalid = || (400, format!("invalid percent-encoding: {}", src));
+
+    let mut bytes = Vec::with_capacity(src.len());
+    let mut iter = src.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [
+                iter.next().ok_or_else(invalid)?,
+                iter.next().ok_or_else(invalid)?,
+            ];
+            let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
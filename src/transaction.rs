@@ -0,0 +1,105 @@
+use crate::UsageInformation;
+use std::ops::{Deref, DerefMut};
+
+/// Changes to `UsageInformation` that can be undone as a whole.
+///
+/// A transaction keeps a snapshot of the data from when it was started. All changes are made to
+/// the wrapped data directly, which the transaction dereferences to. `commit()` keeps the changes,
+/// `rollback()` restores the snapshot. Dropping a transaction without committing it rolls it back.
+/// Only the data is restored, registered callbacks and the clock are kept.
+///
+/// Taking the snapshot clones all data. For large data sets, `UsageInformation::is_dirty()` is the
+/// cheaper way to find out whether anything was changed, but it can't undo changes and also
+/// reports changes that were reverted manually.
+pub struct Transaction<'a> {
+    info: &'a mut UsageInformation,
+    snapshot: Option<UsageInformation>,
+}
+
+impl<'a> Transaction<'a> {
+    /// Starts a transaction on `info`.
+    pub fn new(info: &'a mut UsageInformation) -> Self {
+        let snapshot = Some(info.clone());
+        Self { info, snapshot }
+    }
+
+    /// Specifies whether the data differs from the snapshot taken when the transaction started.
+    ///
+    /// Changes that were reverted within the transaction don't count.
+    pub fn changed(&self) -> bool {
+        self.snapshot.as_ref() != Some(&*self.info)
+    }
+
+    /// Keeps all changes made within the transaction.
+    ///
+    /// Returns whether the data differs from the snapshot, i.e. whether it needs to be saved.
+    pub fn commit(mut self) -> bool {
+        let changed = self.changed();
+        self.snapshot = None;
+        changed
+    }
+
+    /// Undoes all changes made within the transaction.
+    pub fn rollback(self) {
+        // restoring happens when the transaction is dropped
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = UsageInformation;
+
+    fn deref(&self) -> &UsageInformation {
+        self.info
+    }
+}
+
+impl DerefMut for Transaction<'_> {
+    fn deref_mut(&mut self) -> &mut UsageInformation {
+        self.info
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if let Some(snapshot) = self.snapshot.take() {
+            // callbacks and settings belong to the instance, not to the data, so they are kept
+            self.info.usage_information = snapshot.usage_information;
+            self.info.aliases = snapshot.aliases;
+            self.info.groups = snapshot.groups;
+            self.info.dirty = snapshot.dirty;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedClock;
+    use chrono::{TimeZone, Utc};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn rollback_keeps_callbacks_and_clock() {
+        let now = Utc.ymd(2021, 1, 1).and_hms(12, 0, 0);
+        let recorded = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&recorded);
+        let mut info = UsageInformation::new();
+        info.set_clock(Arc::new(FixedClock(now)));
+        info.on_record(Box::new(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let mut transaction = Transaction::new(&mut info);
+        transaction.record_use("milk", true).unwrap();
+        transaction.rollback();
+        assert!(info.list().is_empty());
+        assert!(!info.is_dirty());
+
+        info.record_use("bread", true).unwrap();
+        assert_eq!(recorded.load(Ordering::SeqCst), 2);
+        assert_eq!(info.usages(&"bread".to_owned()).unwrap().list(), vec![now]);
+    }
+}
@@ -1,36 +1,687 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use crate::{DurationUnit, Precision};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::{collections::BTreeMap, convert::TryFrom};
+
+/// A single recorded usage of an object.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Usage {
+    /// When the object was used, or when the session started.
+    at: DateTime<Utc>,
+    /// Where the usage came from, e.g. a host or script.
+    source: Option<String>,
+    /// When the session started by the usage ended.
+    end: Option<DateTime<Utc>>,
+    /// Whether the usage started the session that is still running.
+    running: bool,
+}
+
+impl Usage {
+    /// Provides when the object was used, or when the session started.
+    pub fn at(&self) -> &DateTime<Utc> {
+        &self.at
+    }
+
+    /// Provides when the session started by the usage ended.
+    ///
+    /// Returns `None` for point usages and for the session that is still running.
+    pub fn end(&self) -> Option<&DateTime<Utc>> {
+        self.end.as_ref()
+    }
+
+    /// Specifies whether the usage started the session that is still running.
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    /// Provides where the usage came from, if that is known.
+    pub fn source(&self) -> Option<&String> {
+        self.source.as_ref()
+    }
+}
+
+impl Serialize for Usage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        UsageRef::new(self, |at| *at).serialize(serializer)
+    }
+}
 
 /// Keeps track of the usages of an object.
+///
+/// A usage can start a session with a duration, e.g. an editor that was open for two hours. The
+/// session is still recorded as a single usage at its start, so counts and rates treat sessions
+/// like any other usage. Only `total_time()` takes their durations into account.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "RawUsages")]
 pub struct Usages {
-    /// All recorded usages of something.
-    usages: Vec<DateTime<Utc>>,
+    /// All recorded usages of something, in chronological order. Usages at the same point in time
+    /// are kept in the order they were recorded in.
+    usages: Vec<Usage>,
+    /// When the object started being tracked.
+    created_at: DateTime<Utc>,
+    /// Whether the object is archived.
+    archived: bool,
+    /// How often the object should be used, e.g. 5 times per week.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_rate: Option<(u32, DurationUnit)>,
 }
 
 impl Usages {
+    /// Specifies whether the object is archived.
+    pub fn archived(&self) -> bool {
+        self.archived
+    }
+
     /// Removes all recorded usages.
     pub fn clear(&mut self) {
         self.usages.clear();
     }
 
-    /// Provides read access to all stored data.
-    pub fn list(&self) -> &Vec<DateTime<Utc>> {
+    /// Provides the number of usages at or after `since`.
+    ///
+    /// This uses a binary search, which relies on the usages always being kept in chronological
+    /// order.
+    pub fn count_since(&self, since: &DateTime<Utc>) -> usize {
+        self.usages.len() - self.usages.partition_point(|u| u.at < *since)
+    }
+
+    /// Provides the number of usages on every day between `from` and `to` (both inclusive).
+    ///
+    /// Days are determined in the timezone `tz`. Days without usages are included with a count of
+    /// zero.
+    pub fn count_per_day<Tz: TimeZone>(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        tz: &Tz,
+    ) -> Vec<(NaiveDate, usize)> {
+        let mut counts = BTreeMap::new();
+        let mut day = from;
+        while day <= to {
+            counts.insert(day, 0);
+            day = day.succ();
+        }
+
+        for u in &self.usages {
+            if let Some(count) = counts.get_mut(&u.at.with_timezone(tz).naive_local().date()) {
+                *count += 1;
+            }
+        }
+
+        counts.into_iter().collect()
+    }
+
+    /// Provides the number of usages within segments of the day, e.g. morning and evening.
+    ///
+    /// `starts` contains the hour each segment starts at, in ascending order. A segment lasts until
+    /// the next one starts, the last one wraps around midnight until the first one starts. So every
+    /// usage is in exactly one segment. Hours are determined in the timezone `tz`.
+    pub fn count_per_segment<Tz: TimeZone>(&self, starts: &[u32], tz: &Tz) -> Vec<usize> {
+        count_per_segment(&self.usages, starts, tz)
+    }
+
+    /// Provides the point in time when the object started being tracked.
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    /// Provides all recorded usages with their details, in chronological order.
+    pub fn entries(&self) -> &[Usage] {
         &self.usages
     }
 
-    /// Creates a new, empty Usages object.
+    /// Provides the first recorded usage, if the object was ever used.
+    pub fn first(&self) -> Option<&DateTime<Utc>> {
+        self.usages.first().map(Usage::at)
+    }
+
+    /// Provides how long the object was tracked before it was used for the first time.
+    ///
+    /// Usages recorded for a time before the object was created (e.g. by importing them) count as
+    /// being used immediately. If the object was never used, `None` is returned.
+    pub fn idle_before_first_use(&self) -> Option<Duration> {
+        self.first()
+            .map(|first| (*first - self.created_at).max(Duration::zero()))
+    }
+
+    /// Provides the last recorded usage, if the object was ever used.
+    pub fn last(&self) -> Option<&DateTime<Utc>> {
+        self.usages.last().map(Usage::at)
+    }
+
+    /// Provides the points in time of all recorded usages, in chronological order.
+    ///
+    /// Use `entries()` to get the details of the usages as well.
+    pub fn list(&self) -> Vec<DateTime<Utc>> {
+        self.usages.iter().map(|u| u.at).collect()
+    }
+
+    /// Adds all usages of `other`.
+    ///
+    /// Usages at the same point in time are all kept, as an object can be used several times at
+    /// once. Afterwards the usages are sorted chronologically. The earlier creation time is kept.
+    /// The target rate of `other` is only used if this object has none.
+    pub fn merge(&mut self, mut other: Usages) {
+        // only a single session can be running, so the one of this object wins
+        if self.running_since().is_some() {
+            other.usages.iter_mut().for_each(|u| u.running = false);
+        }
+
+        // both sides are sorted already, so the stable sort only has to merge the two runs
+        self.usages.extend(other.usages);
+        self.usages.sort_by_key(|u| u.at);
+        self.created_at = self.created_at.min(other.created_at);
+        self.target_rate = self.target_rate.or(other.target_rate);
+    }
+
+    /// Creates a new, empty Usages object, created now according to the system clock.
     pub fn new() -> Self {
-        Self { usages: Vec::new() }
+        Self::new_at(Utc::now())
+    }
+
+    /// Creates a new, empty Usages object, created at `created_at`.
+    pub fn new_at(created_at: DateTime<Utc>) -> Self {
+        Self {
+            usages: Vec::new(),
+            created_at,
+            archived: false,
+            target_rate: None,
+        }
+    }
+
+    /// Provides the `n`th most recent usage, where `0` is the most recent one.
+    ///
+    /// If the object was used `n` times or fewer, `None` is returned.
+    pub fn nth_recent(&self, n: usize) -> Option<&Usage> {
+        self.usages.iter().rev().nth(n)
     }
 
     /// Removes all recorded usages from before the value of the `before` parameter.
-    pub fn prune(&mut self, before: DateTime<Utc>) {
-        self.usages.retain(|u| u >= &before);
+    ///
+    /// Returns the number of removed usages.
+    pub fn prune(&mut self, before: DateTime<Utc>) -> usize {
+        let len = self.usages.len();
+        self.usages.retain(|u| u.at >= before);
+        len - self.usages.len()
     }
 
     /// Records a new usage of an object.
-    pub fn record_usage(&mut self) {
-        self.usages.push(Utc::now());
+    ///
+    /// Returns the timestamp of the new usage.
+    pub fn record_usage(&mut self) -> DateTime<Utc> {
+        self.record_usage_with_precision(Precision::default())
+    }
+
+    /// Records a new usage of an object, truncated to the specified `precision`.
+    ///
+    /// Returns the timestamp of the new usage.
+    pub fn record_usage_with_precision(&mut self, precision: Precision) -> DateTime<Utc> {
+        self.record_usage_at(Utc::now(), precision)
+    }
+
+    /// Records a new usage of an object at `timestamp`, truncated to the specified `precision`.
+    ///
+    /// Returns the timestamp of the new usage.
+    pub fn record_usage_at(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        precision: Precision,
+    ) -> DateTime<Utc> {
+        self.record_usage_from(timestamp, None, precision)
+    }
+
+    /// Replaces the source of every usage that has one by the result of `f`.
+    pub(crate) fn map_sources(&mut self, mut f: impl FnMut(&String) -> String) {
+        for usage in &mut self.usages {
+            usage.source = usage.source.as_ref().map(&mut f);
+        }
+    }
+
+    /// Records a new usage of an object at `timestamp`, that came from `source`. The timestamp is
+    /// truncated to the specified `precision`.
+    ///
+    /// Returns the timestamp of the new usage.
+    pub fn record_usage_from(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        source: Option<String>,
+        precision: Precision,
+    ) -> DateTime<Utc> {
+        let timestamp = precision.truncate_to(timestamp);
+
+        // keep the usages in chronological order, even if the timestamp is in the past
+        let index = self.usages.partition_point(|u| u.at <= timestamp);
+        let usage = Usage {
+            at: timestamp,
+            source,
+            end: None,
+            running: false,
+        };
+        self.usages.insert(index, usage);
+        timestamp
+    }
+
+    /// Removes usages recorded at exactly `timestamp`, together with their details.
+    ///
+    /// If several usages share the timestamp, only the one recorded last is removed, unless `all`
+    /// is set. Returns the number of removed usages.
+    pub fn remove_at(&mut self, timestamp: DateTime<Utc>, all: bool) -> usize {
+        let end = self.usages.partition_point(|u| u.at <= timestamp);
+        let start = match all {
+            true => self.usages.partition_point(|u| u.at < timestamp),
+            false => end - usize::from(end > 0 && self.usages[end - 1].at == timestamp),
+        };
+
+        self.usages.drain(start..end);
+        end - start
+    }
+
+    /// Provides the usage that started the session that is still running, if there is one.
+    pub fn running_since(&self) -> Option<&DateTime<Utc>> {
+        self.usages.iter().rev().find(|u| u.running).map(Usage::at)
+    }
+
+    /// Archives or unarchives the object.
+    pub fn set_archived(&mut self, archived: bool) {
+        self.archived = archived;
+    }
+
+    /// Sets the point in time when the object started being tracked.
+    pub fn set_created_at(&mut self, created_at: DateTime<Utc>) {
+        self.created_at = created_at;
+    }
+
+    /// Sets how often the object should be used, or removes the target rate if `None` is passed.
+    pub fn set_target_rate(&mut self, target_rate: Option<(u32, DurationUnit)>) {
+        self.target_rate = target_rate;
+    }
+
+    /// Enforces the invariants of the recorded usages, by sorting them chronologically. Usages at
+    /// the same point in time are all kept, in the order they were stored in.
+    ///
+    /// Returns whether anything had to be changed.
+    pub fn rebuild(&mut self) -> bool {
+        let sorted = self.usages.windows(2).all(|w| w[0].at <= w[1].at);
+        self.usages.sort_by_key(|u| u.at);
+        !sorted
+    }
+
+    /// Marks the usage recorded last at `start` as the start of a running session.
+    ///
+    /// A session that was still running before is dropped without an end, so its usage becomes a
+    /// point usage. If there is no usage at `start`, no session is running afterwards.
+    pub fn start_session(&mut self, start: DateTime<Utc>) {
+        self.usages.iter_mut().for_each(|u| u.running = false);
+        if let Some(usage) = self.usages.iter_mut().rev().find(|u| u.at == start) {
+            usage.running = true;
+        }
+    }
+
+    /// Ends the running session at `end`, or at its start if `end` is earlier.
+    ///
+    /// Returns the duration of the session, or `None` if no session is running.
+    pub fn stop_session(&mut self, end: DateTime<Utc>) -> Option<Duration> {
+        let usage = self.usages.iter_mut().rev().find(|u| u.running)?;
+        let end = end.max(usage.at);
+        usage.running = false;
+        usage.end = Some(end);
+        Some(end - usage.at)
+    }
+
+    /// Provides how often the object should be used, if a target rate is set.
+    pub fn target_rate(&self) -> Option<(u32, DurationUnit)> {
+        self.target_rate
+    }
+
+    /// Provides the summed up duration of all sessions.
+    ///
+    /// The session that is still running counts until `now`. Point usages don't count.
+    pub fn total_time(&self, now: DateTime<Utc>) -> Duration {
+        self.usages
+            .iter()
+            .fold(Duration::zero(), |total, u| match (u.end, u.running) {
+                (Some(end), _) => total + (end - u.at),
+                (None, true) => total + (now - u.at).max(Duration::zero()),
+                (None, false) => total,
+            })
+    }
+}
+
+/// A view of `Usages` that serializes the usages as seconds since the Unix epoch, see
+/// `UsageInformation::epoch_seconds()`.
+///
+/// The ends of sessions are stored as seconds since the Unix epoch as well.
+#[derive(Serialize)]
+pub(crate) struct EpochSecondsUsages<'a> {
+    usages: Vec<UsageRef<'a, i64>>,
+    created_at: &'a DateTime<Utc>,
+    archived: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_rate: Option<(u32, DurationUnit)>,
+}
+
+impl<'a> From<&'a Usages> for EpochSecondsUsages<'a> {
+    fn from(usages: &'a Usages) -> Self {
+        Self {
+            usages: usages
+                .usages
+                .iter()
+                .map(|u| UsageRef::new(u, |at| at.timestamp()))
+                .collect(),
+            created_at: &usages.created_at,
+            archived: usages.archived,
+            target_rate: usages.target_rate,
+        }
+    }
+}
+
+/// The serialized form of a `Usage`, with its points in time converted to `T`.
+///
+/// Usages without details are stored as a bare point in time, like all usages were before they had
+/// details. This keeps files small and readable.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum UsageRef<'a, T> {
+    Bare(T),
+    Detailed {
+        at: T,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source: Option<&'a String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        end: Option<T>,
+        #[serde(skip_serializing_if = "is_false")]
+        running: bool,
+    },
+}
+
+impl<'a, T> UsageRef<'a, T> {
+    /// Creates the serialized form of `usage`, converting its points in time with `convert`.
+    fn new(usage: &'a Usage, convert: impl Fn(&DateTime<Utc>) -> T) -> Self {
+        match (&usage.source, &usage.end, usage.running) {
+            (None, None, false) => UsageRef::Bare(convert(&usage.at)),
+            _ => UsageRef::Detailed {
+                at: convert(&usage.at),
+                source: usage.source.as_ref(),
+                end: usage.end.as_ref().map(&convert),
+                running: usage.running,
+            },
+        }
+    }
+}
+
+/// Provides the number of `usages` within segments of the day, see `Usages::count_per_segment()`.
+pub(crate) fn count_per_segment<Tz: TimeZone>(
+    usages: &[Usage],
+    starts: &[u32],
+    tz: &Tz,
+) -> Vec<usize> {
+    let mut counts = vec![0; starts.len()];
+    if starts.is_empty() {
+        return counts;
+    }
+
+    for u in usages {
+        let hour = u.at.with_timezone(tz).hour();
+        let segment = match starts.iter().rposition(|start| *start <= hour) {
+            Some(segment) => segment,
+            None => starts.len() - 1,
+        };
+        counts[segment] += 1;
+    }
+
+    counts
+}
+
+/// Used to skip serializing `false`.
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// The serialized form of `Usages`, as it might have been written by older versions.
+///
+/// Files written before the creation time was tracked don't contain `created_at`. In that case,
+/// the first usage (or, if there is none, the Unix epoch) is used instead. The fallback must not
+/// depend on when the file is loaded, otherwise the creation time would change on every load. Files
+/// written before objects could be archived don't contain `archived`, so those objects aren't
+/// archived. Files written before target rates existed don't contain `target_rate`, so no target is
+/// set.
+///
+/// Usages may be stored as RFC 3339 strings or as seconds since the Unix epoch, see
+/// `EpochSecondsUsages`.
+///
+/// The usages are sorted chronologically, because older versions didn't guarantee that order.
+#[derive(Deserialize)]
+struct RawUsages {
+    usages: Vec<RawUsage>,
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    target_rate: Option<(u32, DurationUnit)>,
+}
+
+impl From<RawUsages> for Usages {
+    fn from(raw: RawUsages) -> Self {
+        let mut usages: Vec<_> = raw.usages.into_iter().map(RawUsage::resolve).collect();
+        let created_at = raw
+            .created_at
+            .or_else(|| usages.iter().map(|u| u.at).min())
+            .unwrap_or_else(|| Utc.timestamp(0, 0));
+
+        // files might have been edited manually, so the order isn't guaranteed
+        usages.sort_by_key(|u| u.at);
+
+        Self {
+            usages,
+            created_at,
+            archived: raw.archived,
+            target_rate: raw.target_rate,
+        }
+    }
+}
+
+/// A serialized usage, either as a bare point in time or with details, see `UsageRef`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawUsage {
+    Bare(Timestamp),
+    Detailed {
+        at: Timestamp,
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(default)]
+        end: Option<Timestamp>,
+        #[serde(default)]
+        running: bool,
+    },
+}
+
+impl RawUsage {
+    /// Converts the serialized usage into a usage.
+    fn resolve(self) -> Usage {
+        match self {
+            RawUsage::Bare(at) => Usage {
+                at: at.0,
+                source: None,
+                end: None,
+                running: false,
+            },
+            RawUsage::Detailed {
+                at,
+                source,
+                end,
+                running,
+            } => Usage {
+                at: at.0,
+                source,
+                end: end.map(|end| end.0),
+                running,
+            },
+        }
+    }
+}
+
+/// A point in time read from a data file, see `RawTimestamp`.
+#[derive(Deserialize)]
+#[serde(try_from = "RawTimestamp")]
+struct Timestamp(DateTime<Utc>);
+
+impl TryFrom<RawTimestamp> for Timestamp {
+    type Error = String;
+
+    /// Converts the serialized point in time into a point in time.
+    ///
+    /// Fails if seconds since the Unix epoch are outside the range chrono can represent.
+    fn try_from(raw: RawTimestamp) -> Result<Self, Self::Error> {
+        match raw {
+            RawTimestamp::Rfc3339(timestamp) => Ok(Timestamp(timestamp)),
+            RawTimestamp::EpochSeconds(secs) => Utc
+                .timestamp_opt(secs, 0)
+                .single()
+                .map(Timestamp)
+                .ok_or_else(|| format!("timestamp out of range: {}", secs)),
+        }
+    }
+}
+
+/// A serialized point in time, either as an RFC 3339 string or as seconds since the Unix epoch.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTimestamp {
+    Rfc3339(DateTime<Utc>),
+    EpochSeconds(i64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_never_used_objects_get_a_stable_creation_time() {
+        let json = r#"{"usages":[]}"#;
+        let first: Usages = serde_json::from_str(json).unwrap();
+        let second: Usages = serde_json::from_str(json).unwrap();
+
+        assert_eq!(first.created_at(), &Utc.timestamp(0, 0));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn merge_keeps_usages_at_the_same_time() {
+        let first = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let second = Utc.ymd(2021, 1, 2).and_hms(0, 0, 0);
+
+        let mut usages = Usages::new();
+        usages.record_usage_at(second, Precision::Nanos);
+        usages.record_usage_at(first, Precision::Nanos);
+        let mut other = Usages::new();
+        other.record_usage_at(second, Precision::Nanos);
+
+        usages.merge(other);
+        assert_eq!(usages.list(), vec![first, second, second]);
+    }
+
+    #[test]
+    fn rebuild_keeps_usages_at_the_same_time() {
+        let first = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let second = Utc.ymd(2021, 1, 2).and_hms(0, 0, 0);
+
+        let mut usages = Usages::new();
+        usages.record_usage_at(second, Precision::Nanos);
+        usages.record_usage_at(second, Precision::Nanos);
+        usages.record_usage_at(first, Precision::Nanos);
+        usages.usages.reverse();
+
+        assert!(usages.rebuild());
+        assert_eq!(usages.list(), vec![first, second, second]);
+        assert!(!usages.rebuild());
+    }
+
+    #[test]
+    fn count_since_matches_a_naive_count() {
+        let start = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let mut usages = Usages::new();
+
+        // record in a scrambled order, including several usages at the same time
+        for i in 0..100 {
+            let hours = (i * 37) % 50;
+            usages.record_usage_at(start + Duration::hours(hours), Precision::Nanos);
+        }
+
+        for hours in -1..=51 {
+            let since = start + Duration::hours(hours);
+            let naive = usages.list().iter().filter(|u| **u >= since).count();
+            assert_eq!(usages.count_since(&since), naive, "since {}", since);
+        }
+    }
+
+    #[test]
+    fn usages_at_the_same_time_keep_their_own_sources() {
+        let at = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let mut usages = Usages::new();
+        usages.record_usage_from(at, Some("laptop".to_owned()), Precision::Nanos);
+        usages.record_usage_from(at, Some("phone".to_owned()), Precision::Nanos);
+
+        let json = serde_json::to_string(&usages).unwrap();
+        let loaded: Usages = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, usages);
+
+        usages.remove_at(at, false);
+        let sources: Vec<_> = usages.entries().iter().map(Usage::source).collect();
+        assert_eq!(sources, vec![Some(&"laptop".to_owned())]);
+    }
+
+    #[test]
+    fn epoch_seconds_round_trip() {
+        let created_at = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let at = Utc.ymd(2021, 1, 2).and_hms(8, 30, 15);
+        let mut usages = Usages::new_at(created_at);
+        usages.record_usage_at(at, Precision::Nanos);
+        usages.record_usage_from(at, Some("laptop".to_owned()), Precision::Nanos);
+        usages.start_session(at);
+        usages.stop_session(at + Duration::hours(2));
+        usages.record_usage_at(at + Duration::days(1), Precision::Nanos);
+        usages.start_session(at + Duration::days(1));
+
+        let json = serde_json::to_value(&EpochSecondsUsages::from(&usages)).unwrap();
+        assert_eq!(json["usages"][0], serde_json::json!(at.timestamp()));
+        let loaded: Usages = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded, usages);
+    }
+
+    #[test]
+    fn out_of_range_epoch_seconds_are_rejected() {
+        for secs in [i64::MAX, i64::MIN] {
+            let json = format!(r#"{{"usages":[{}]}}"#, secs);
+            assert!(serde_json::from_str::<Usages>(&json).is_err(), "{}", secs);
+
+            let json = format!(r#"{{"usages":[{{"at":0,"end":{}}}]}}"#, secs);
+            assert!(serde_json::from_str::<Usages>(&json).is_err(), "{}", secs);
+        }
+    }
+
+    #[test]
+    fn usages_without_details_are_stored_as_timestamps() {
+        let mut usages = Usages::new_at(Utc.ymd(2021, 1, 1).and_hms(0, 0, 0));
+        let at = Utc.ymd(2021, 1, 2).and_hms(0, 0, 0);
+        usages.record_usage_at(at, Precision::Nanos);
+        usages.record_usage_from(at, Some("laptop".to_owned()), Precision::Nanos);
+
+        let json = serde_json::to_value(&usages).unwrap();
+        assert_eq!(
+            json["usages"],
+            serde_json::json!([
+                "2021-01-02T00:00:00Z",
+                { "at": "2021-01-02T00:00:00Z", "source": "laptop" }
+            ])
+        );
+    }
+
+    #[test]
+    fn legacy_objects_are_created_at_their_first_use() {
+        let json = r#"{"usages":["2021-01-02T00:00:00Z","2021-01-01T00:00:00Z"]}"#;
+        let usages: Usages = serde_json::from_str(json).unwrap();
+
+        assert_eq!(usages.created_at(), &Utc.ymd(2021, 1, 1).and_hms(0, 0, 0));
     }
 }